@@ -1,5 +1,7 @@
 use web_sys::{AudioContext, OscillatorType};
 
+use crate::powerups::PowerUp;
+
 pub struct AudioPlayer {
     context: Option<AudioContext>,
 }
@@ -66,6 +68,35 @@ impl AudioPlayer {
         }
     }
 
+    /// A short motif per power-up, so each can be told apart by ear.
+    pub fn play_power_up(&self, power: PowerUp) {
+        if let Some(ctx) = &self.context {
+            let now = ctx.current_time();
+            let notes: &[f32] = match power {
+                PowerUp::Ghost => &[880.0, 660.0, 880.0],
+                PowerUp::Magnet => &[400.0, 500.0, 400.0],
+                PowerUp::SlowMo => &[700.0, 500.0, 350.0],
+                PowerUp::DoubleScore => &[800.0, 800.0, 1600.0],
+            };
+            for (i, &freq) in notes.iter().enumerate() {
+                self.play_tone(ctx, freq, now + i as f64 * 0.08, 0.08);
+            }
+        }
+    }
+
+    pub fn play_power_down(&self) {
+        if let Some(ctx) = &self.context {
+            let now = ctx.current_time();
+            self.play_tone(ctx, 500.0, now, 0.1);
+            self.play_tone(ctx, 350.0, now + 0.1, 0.15);
+        }
+    }
+
+    /// A short click for an item about to disappear.
+    pub fn play_warning_tick(&self) {
+        self.play_sound(1500.0, 0.03);
+    }
+
     pub fn play_game_over(&self) {
         if let Some(ctx) = &self.context {
             let now = ctx.current_time();
@@ -75,6 +106,17 @@ impl AudioPlayer {
         }
     }
 
+    pub fn play_victory(&self) {
+        if let Some(ctx) = &self.context {
+            let now = ctx.current_time();
+            // Rising major arpeggio, ending on a held octave
+            self.play_tone(ctx, 523.0, now, 0.15);
+            self.play_tone(ctx, 659.0, now + 0.15, 0.15);
+            self.play_tone(ctx, 784.0, now + 0.3, 0.15);
+            self.play_tone(ctx, 1047.0, now + 0.45, 0.6);
+        }
+    }
+
     fn play_tone(&self, ctx: &AudioContext, freq: f32, start_time: f64, duration: f64) {
          let oscillator = match ctx.create_oscillator() {
                 Ok(o) => o,
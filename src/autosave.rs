@@ -0,0 +1,51 @@
+use crate::game::GameState;
+use crate::save;
+
+const SAVE_KEY: &str = "snake3d_save";
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Stores `game` so it can be continued on the next load. Finished or
+/// untouched games aren't worth continuing, so they clear the save instead.
+pub fn store_game(game: &GameState) {
+    if game.game_over || game.ticks == 0 {
+        clear_saved_game();
+        return;
+    }
+    let Some(storage) = storage() else { return };
+    match save::to_json(game) {
+        Ok(json) => {
+            let _ = storage.set_item(SAVE_KEY, &json);
+        }
+        Err(err) => log::warn!("Could not save game: {err}"),
+    }
+}
+
+/// The saved game, if there is one this version can read. Unreadable saves
+/// are dropped so they aren't offered again.
+pub fn load_saved_game() -> Option<GameState> {
+    let json = storage()?.get_item(SAVE_KEY).ok()??;
+    match save::from_json(&json) {
+        Ok(game) => Some(game),
+        Err(err) => {
+            log::warn!("Discarding saved game: {err}");
+            clear_saved_game();
+            None
+        }
+    }
+}
+
+pub fn clear_saved_game() {
+    if let Some(storage) = storage() {
+        let _ = storage.remove_item(SAVE_KEY);
+    }
+}
+
+pub fn show_continue_prompt(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(prompt) = document.get_element_by_id("continue-prompt") {
+        prompt.class_list().toggle_with_force("hidden", !visible).unwrap_or(false);
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::food::FoodTable;
+use crate::game::{GameConfig, GameState};
+use crate::obstacles::ObstacleLayout;
+use crate::replay::MAX_VERIFIED_SIDE;
+use crate::topology::{Cube, Topology};
+
+/// A hand-made game: a name plus the config it's played with. In JSON the
+/// config's fields sit alongside the name, and all but the board size are
+/// optional.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    #[serde(flatten)]
+    pub config: GameConfig,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Json(serde_json::Error),
+    /// The level parsed but can't be played; the string says which level and why.
+    Invalid(String),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Json(err) => write!(f, "bad level file: {err}"),
+            LevelError::Invalid(reason) => write!(f, "invalid level: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    pub fn from_json(json: &str) -> Result<Self, LevelError> {
+        let level: Level = serde_json::from_str(json).map_err(LevelError::Json)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    /// Checks what the format can't: board size, and that the start,
+    /// scripted food and goals make sense on it.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        let invalid = |reason: &str| Err(LevelError::Invalid(format!("{}: {reason}", self.name)));
+        let config = &self.config;
+        if [config.width, config.height, config.depth].iter().any(|side| !(1..=MAX_VERIFIED_SIDE).contains(side)) {
+            return invalid("board size out of range");
+        }
+        let cube = Cube::cuboid(config.width, config.height, config.depth);
+        if config.start.is_some_and(|start| !cube.contains(start.pos)) {
+            return invalid("start is off the board");
+        }
+        if !config.food_script.iter().all(|&pos| cube.contains(pos)) {
+            return invalid("scripted food is off the board");
+        }
+        if config.target_score == Some(0) {
+            return invalid("target score must be positive");
+        }
+        if config.time_limit.is_some_and(|limit| limit.is_nan() || limit <= 0.0) {
+            return invalid("time limit must be positive");
+        }
+        if config.food_count == 0 {
+            return invalid("food count must be positive");
+        }
+        if let FoodTable::Weighted(weights) = &config.food_table {
+            match weights.iter().try_fold(0u32, |total, entry| total.checked_add(entry.weight)) {
+                Some(0) => return invalid("food table needs a positive weight"),
+                None => return invalid("food weights add up to too much"),
+                Some(_) => {}
+            }
+        }
+        if config.prize_lifetime == Some(0) {
+            return invalid("prize lifetime must be positive");
+        }
+        if let ObstacleLayout::Generated { density, .. } = config.obstacles {
+            if !(0.0..=1.0).contains(&density) {
+                return invalid("obstacle density must be between 0 and 1");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn start(&self, seed: u64) -> GameState {
+        GameState::with_config(self.config.clone(), seed)
+    }
+}
+
+/// Levels played in order, each unlocked by finishing the one before.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+}
+
+impl Campaign {
+    pub fn from_json(json: &str) -> Result<Self, LevelError> {
+        let campaign: Campaign = serde_json::from_str(json).map_err(LevelError::Json)?;
+        campaign.levels.iter().try_for_each(Level::validate)?;
+        Ok(campaign)
+    }
+
+    /// The campaign that ships with the game.
+    pub fn builtin() -> Self {
+        Self::from_json(include_str!("../levels/campaign.json")).expect("built-in campaign is valid")
+    }
+
+    pub fn is_unlocked(&self, index: usize, progress: CampaignProgress) -> bool {
+        index < self.levels.len() && progress.is_unlocked(index)
+    }
+}
+
+/// How far the player has got through a campaign.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    /// Levels finished, counting from the first.
+    pub completed: usize,
+}
+
+impl CampaignProgress {
+    pub fn is_unlocked(self, index: usize) -> bool {
+        index <= self.completed
+    }
+
+    /// Records finishing level `index`, unlocking the next one.
+    pub fn complete(&mut self, index: usize) {
+        if self.is_unlocked(index) {
+            self.completed = self.completed.max(index + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Direction, Face, GameEvent, Position};
+
+    #[test]
+    fn test_builtin_campaign_loads() {
+        let campaign = Campaign::builtin();
+        assert!(campaign.levels.len() >= 3);
+        for (i, level) in campaign.levels.iter().enumerate() {
+            let game = level.start(i as u64);
+            assert!(!game.is_blocked(game.snake.head()), "{}", level.name);
+            assert!(level.config.target_score.is_some(), "{} needs a goal", level.name);
+        }
+    }
+
+    #[test]
+    fn test_level_sets_start_and_scripted_food() {
+        let level = Level::from_json(
+            r#"{
+                "name": "Test",
+                "width": 5, "height": 5, "depth": 5,
+                "start": { "pos": { "face": "Top", "u": 1, "v": 1 }, "heading": "Right" },
+                "food_script": [{ "face": "Top", "u": 2, "v": 1 }, { "face": "Top", "u": 4, "v": 1 }],
+                "target_score": 2
+            }"#,
+        )
+        .unwrap();
+        let mut game = level.start(7);
+        assert_eq!(game.snake.head(), Position { face: Face::Top, u: 1, v: 1 });
+        assert_eq!(game.snake.direction, Direction::Right);
+        assert_eq!(game.foods[0].pos, Position { face: Face::Top, u: 2, v: 1 });
+
+        game.update();
+        assert_eq!(game.foods[0].pos, Position { face: Face::Top, u: 4, v: 1 });
+        game.update();
+        let events = game.update();
+        assert!(matches!(events.last(), Some(GameEvent::TargetReached(_))));
+        assert!(game.won);
+    }
+
+    #[test]
+    fn test_time_limit_ends_the_game() {
+        let mut config = GameConfig::cube(20);
+        config.time_limit = Some(1.0);
+        let mut game = GameState::with_config(config, 2);
+        let mut ticks = 0;
+        while !game.game_over {
+            game.update();
+            ticks += 1;
+        }
+        assert!(!game.won);
+        assert_eq!(ticks, 7, "0.15s per move runs out on the seventh");
+    }
+
+    #[test]
+    fn test_invalid_levels_are_rejected() {
+        let off_board = r#"{"name": "Bad", "width": 4, "height": 4, "depth": 4,
+            "start": { "pos": { "face": "Front", "u": 9, "v": 0 }, "heading": "Up" }}"#;
+        assert!(matches!(Level::from_json(off_board), Err(LevelError::Invalid(_))));
+        let no_food = r#"{"name": "Bad", "width": 4, "height": 4, "depth": 4, "food_count": 0}"#;
+        assert!(matches!(Level::from_json(no_food), Err(LevelError::Invalid(_))));
+        let tables = [
+            r#"[]"#,
+            r#"[{"kind": "Normal", "weight": 0}, {"kind": "Prize", "weight": 0}]"#,
+            r#"[{"kind": "Normal", "weight": 4294967295}, {"kind": "Prize", "weight": 1}]"#,
+        ];
+        for table in tables {
+            let json = format!(r#"{{"name": "Bad", "width": 4, "height": 4, "depth": 4, "food_table": {{"Weighted": {table}}}}}"#);
+            assert!(matches!(Level::from_json(&json), Err(LevelError::Invalid(_))), "{table}");
+        }
+        assert!(matches!(Level::from_json("{}"), Err(LevelError::Json(_))));
+    }
+
+    #[test]
+    fn test_levels_unlock_in_sequence() {
+        let campaign = Campaign::builtin();
+        let mut progress = CampaignProgress::default();
+        assert!(campaign.is_unlocked(0, progress));
+        assert!(!campaign.is_unlocked(1, progress));
+
+        progress.complete(2);
+        assert!(!campaign.is_unlocked(1, progress), "can't skip ahead");
+        progress.complete(0);
+        progress.complete(1);
+        assert!(campaign.is_unlocked(2, progress));
+        assert!(!campaign.is_unlocked(campaign.levels.len(), CampaignProgress { completed: 99 }));
+    }
+}
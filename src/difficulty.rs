@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+/// No curve moves faster than this, however it's configured.
+pub const MIN_MOVE_INTERVAL: f64 = 0.02;
+
+/// What a speed curve speeds up with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeedDriver {
+    Score,
+    /// Snake length in segments.
+    Length,
+    /// Seconds of play, counted in game time so replays agree.
+    Time,
+}
+
+/// Seconds per move, falling linearly from `start` by `step` per unit of the
+/// driver until it reaches `min`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpeedCurve {
+    pub start: f64,
+    pub min: f64,
+    pub step: f64,
+    pub driver: SpeedDriver,
+}
+
+impl SpeedCurve {
+    /// Seconds per move once the driver has reached `progress`.
+    pub fn interval(&self, progress: f64) -> f64 {
+        (self.start - self.step * progress).max(self.min).max(MIN_MOVE_INTERVAL)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Speeds up with time rather than score, so stalling doesn't help.
+    Insane,
+    Custom(SpeedCurve),
+}
+
+impl Difficulty {
+    pub const PRESETS: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Insane];
+
+    pub fn curve(self) -> SpeedCurve {
+        let (start, min, step, driver) = match self {
+            Difficulty::Easy => (0.2, 0.08, 0.0015, SpeedDriver::Score),
+            Difficulty::Normal => (0.15, 0.05, 0.002, SpeedDriver::Score),
+            Difficulty::Hard => (0.11, 0.04, 0.003, SpeedDriver::Score),
+            Difficulty::Insane => (0.08, 0.03, 0.001, SpeedDriver::Time),
+            Difficulty::Custom(curve) => return curve,
+        };
+        SpeedCurve { start, min, step, driver }
+    }
+
+    /// The next preset, for cycling through them; custom curves go back to Normal.
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Insane,
+            Difficulty::Insane => Difficulty::Easy,
+            Difficulty::Custom(_) => Difficulty::Normal,
+        }
+    }
+
+    /// Stable name for storage keys; also the high-score bucket. Custom
+    /// curves all share one bucket.
+    pub fn key(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+            Difficulty::Custom(_) => "custom",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::PRESETS.into_iter().find(|d| d.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Insane => "Insane",
+            Difficulty::Custom(_) => "Custom",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameConfig, GameState};
+
+    #[test]
+    fn test_normal_keeps_original_pace() {
+        let curve = Difficulty::Normal.curve();
+        assert_eq!(curve.interval(0.0), 0.15);
+        assert!((curve.interval(25.0) - 0.1).abs() < 1e-9);
+        assert_eq!(curve.interval(1000.0), 0.05);
+    }
+
+    #[test]
+    fn test_presets_get_faster() {
+        for progress in [0.0, 10.0, 40.0, 200.0] {
+            let intervals: Vec<f64> = Difficulty::PRESETS.iter().map(|d| d.curve().interval(progress)).collect();
+            assert!(intervals.windows(2).all(|w| w[0] >= w[1]), "{intervals:?} at {progress}");
+        }
+        for d in Difficulty::PRESETS {
+            assert_eq!(Difficulty::from_key(d.key()), Some(d));
+        }
+    }
+
+    #[test]
+    fn test_time_curve_speeds_up_as_the_game_runs() {
+        let config = GameConfig { difficulty: Difficulty::Insane, ..GameConfig::cube(20) };
+        let mut game = GameState::with_config(config, 3);
+        let first = game.move_interval();
+        for _ in 0..5 {
+            game.update();
+        }
+        assert!(game.elapsed > 0.0);
+        assert!(game.move_interval() < first);
+        assert_eq!(game.score, 0, "speed-up came from time, not score");
+    }
+
+    #[test]
+    fn test_custom_curves_cannot_stall_the_scheduler() {
+        let curve = SpeedCurve { start: 0.0, min: -1.0, step: 1.0, driver: SpeedDriver::Length };
+        assert_eq!(Difficulty::Custom(curve).curve().interval(3.0), MIN_MOVE_INTERVAL);
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::Position;
+use crate::powerups::PowerUp;
+use crate::rng::Rng;
+
+/// Segments a shrink item takes off the tail, never leaving less than the head.
+pub const SHRINK_SEGMENTS: usize = 3;
+/// How much slow-down and speed-up items stretch or squeeze the move interval.
+pub const PACE_STEP: f64 = 1.25;
+/// Limits on the combined effect of slow-down and speed-up items.
+pub const PACE_RANGE: (f64, f64) = (0.5, 2.0);
+/// Seconds a bonus-time item adds to the time limit.
+pub const BONUS_SECONDS: f64 = 10.0;
+/// Moves a prize stays on the board in new endless games.
+pub const PRIZE_LIFETIME: u32 = 60;
+/// Moves left at which an expiring prize starts to warn.
+pub const EXPIRY_WARNING_TICKS: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FoodKind {
+    Normal,
+    Prize,
+    /// Takes segments off the tail instead of growing.
+    Shrink,
+    /// Makes the snake move slower from now on.
+    SlowDown,
+    /// Makes the snake move faster from now on.
+    SpeedUp,
+    /// Adds to the time limit, if there is one.
+    BonusTime,
+    /// Starts a timed power-up.
+    PowerUp(PowerUp),
+}
+
+impl FoodKind {
+    /// Points for eating one.
+    pub fn points(self) -> u32 {
+        match self {
+            FoodKind::Normal | FoodKind::Shrink | FoodKind::SlowDown | FoodKind::BonusTime | FoodKind::PowerUp(_) => 1,
+            FoodKind::SpeedUp => 3,
+            FoodKind::Prize => 5,
+        }
+    }
+
+    /// Whether eating one adds a segment.
+    pub fn grows(self) -> bool {
+        self != FoodKind::Shrink
+    }
+}
+
+/// One item on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Food {
+    pub kind: FoodKind,
+    pub pos: Position,
+    /// Tick it appeared on.
+    pub spawned_at: u64,
+}
+
+/// Points for a prize worth `points` eaten on the `age`th move after it
+/// appeared, falling away from the full value on its first move to 1 on the
+/// last move of its `lifetime`.
+pub fn decayed_points(points: u32, age: u64, lifetime: u32) -> u32 {
+    let spread = points.saturating_sub(1) as u64;
+    let lost = spread * age.saturating_sub(1) / lifetime.saturating_sub(1).max(1) as u64;
+    points - lost.min(spread) as u32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoodWeight {
+    pub kind: FoodKind,
+    pub weight: u32,
+}
+
+/// Which kind each new item is.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodTable {
+    /// Normal food, with every fifth item a prize.
+    #[default]
+    Classic,
+    /// Each kind drawn with odds in proportion to its weight, using the game's seed.
+    Weighted(Vec<FoodWeight>),
+}
+
+impl FoodTable {
+    /// The kind of the `index`th item spawned this game (counting from 0).
+    /// Classic tables don't touch `rng`, so games from before weighted
+    /// tables existed replay the same.
+    pub fn pick(&self, index: u32, rng: &mut Rng) -> FoodKind {
+        match self {
+            FoodTable::Classic => {
+                if (index + 1).is_multiple_of(5) {
+                    FoodKind::Prize
+                } else {
+                    FoodKind::Normal
+                }
+            }
+            FoodTable::Weighted(weights) => {
+                let total: u32 = weights.iter().map(|w| w.weight).sum();
+                if total == 0 {
+                    return FoodKind::Normal;
+                }
+                let mut roll = rng.below(total);
+                for entry in weights {
+                    if roll < entry.weight {
+                        return entry.kind;
+                    }
+                    roll -= entry.weight;
+                }
+                unreachable!("roll is below the total weight")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_table_gives_every_fifth_item_as_prize() {
+        let mut rng = Rng::new(1);
+        let kinds: Vec<FoodKind> = (0..10).map(|i| FoodTable::Classic.pick(i, &mut rng)).collect();
+        assert_eq!(kinds.iter().filter(|&&k| k == FoodKind::Prize).count(), 2);
+        assert_eq!(kinds[4], FoodKind::Prize);
+        assert_eq!(kinds[9], FoodKind::Prize);
+        assert_eq!(rng.next_u64(), Rng::new(1).next_u64(), "classic picks draw nothing");
+    }
+
+    #[test]
+    fn test_weighted_table_follows_weights() {
+        let table = FoodTable::Weighted(vec![
+            FoodWeight { kind: FoodKind::Normal, weight: 3 },
+            FoodWeight { kind: FoodKind::Shrink, weight: 0 },
+            FoodWeight { kind: FoodKind::BonusTime, weight: 1 },
+        ]);
+        let mut rng = Rng::new(5);
+        let kinds: Vec<FoodKind> = (0..4000).map(|i| table.pick(i, &mut rng)).collect();
+        let bonus = kinds.iter().filter(|&&k| k == FoodKind::BonusTime).count();
+        assert!(!kinds.contains(&FoodKind::Shrink));
+        assert!((800..1200).contains(&bonus), "{bonus} bonus items in 4000");
+    }
+
+    #[test]
+    fn test_prize_points_decay_to_one_over_the_lifetime() {
+        assert_eq!(decayed_points(5, 1, 40), 5, "full value straight away");
+        assert_eq!(decayed_points(5, 21, 40), 3);
+        assert_eq!(decayed_points(5, 39, 40), 2);
+        assert_eq!(decayed_points(5, 40, 40), 1, "1 on the last move");
+        assert_eq!(decayed_points(5, 500, 40), 1);
+        assert_eq!(decayed_points(1, 30, 40), 1);
+    }
+}
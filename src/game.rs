@@ -1,6 +1,18 @@
 use std::collections::VecDeque;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::{Difficulty, SpeedDriver, MIN_MOVE_INTERVAL};
+use crate::food::{self, Food, FoodKind, FoodTable};
+use crate::obstacles::{self, ObstacleLayout};
+use crate::occupancy::Occupancy;
+use crate::powerups::{self, Effects, PowerUp};
+use crate::rng::{self, Rng};
+use crate::replay::{Replay, TimedInput, MAX_VERIFIED_SIDE, REPLAY_VERSION};
+use crate::save::Snapshot;
+use crate::topology::{Cube, Topology};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Face {
     Front,
     Back,
@@ -10,7 +22,18 @@ pub enum Face {
     Bottom,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl Face {
+    pub const ALL: [Face; 6] = [
+        Face::Front,
+        Face::Back,
+        Face::Left,
+        Face::Right,
+        Face::Top,
+        Face::Bottom,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -18,69 +41,429 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A turn relative to the current heading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// One steering input: an absolute face-local direction, or a turn
+/// relative to whatever the heading is when it gets applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Steer {
+    Absolute(Direction),
+    Relative(Turn),
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Heading after turning, seen from outside the face (u right, v up).
+    pub fn turned(self, turn: Turn) -> Direction {
+        match (self, turn) {
+            (Direction::Up, Turn::Left) | (Direction::Down, Turn::Right) => Direction::Left,
+            (Direction::Up, Turn::Right) | (Direction::Down, Turn::Left) => Direction::Right,
+            (Direction::Left, Turn::Left) | (Direction::Right, Turn::Right) => Direction::Down,
+            (Direction::Left, Turn::Right) | (Direction::Right, Turn::Left) => Direction::Up,
+        }
+    }
+}
+
+impl Steer {
+    /// The direction this input means when applied to `heading`.
+    pub fn resolve(self, heading: Direction) -> Direction {
+        match self {
+            Steer::Absolute(dir) => dir,
+            Steer::Relative(turn) => heading.turned(turn),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub face: Face,
     pub u: i32,
     pub v: i32,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Board size in cells along x, y and z; the default cube topology is built from it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GameConfig {
-    pub grid_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub depth: i32,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub obstacles: ObstacleLayout,
+    /// Where the snake starts; the middle of the front face, heading up, if unset.
+    #[serde(default)]
+    pub start: Option<Start>,
+    /// Cells food appears on, in order, before it starts appearing at random.
+    #[serde(default)]
+    pub food_script: Vec<Position>,
+    /// Score that completes the game.
+    #[serde(default)]
+    pub target_score: Option<u32>,
+    /// Seconds of game time before the game is lost.
+    #[serde(default)]
+    pub time_limit: Option<f64>,
+    /// Items on the board at once.
+    #[serde(default = "default_food_count")]
+    pub food_count: u32,
+    /// Which kind each new item is.
+    #[serde(default)]
+    pub food_table: FoodTable,
+    /// Moves a prize stays on the board, losing points as it goes; forever
+    /// if unset. Unset is the default when reading a config so older replays
+    /// play the same; `GameConfig::cube` sets it.
+    #[serde(default)]
+    pub prize_lifetime: Option<u32>,
+}
+
+fn default_food_count() -> u32 {
+    1
+}
+
+impl GameConfig {
+    pub fn cube(grid_size: i32) -> Self {
+        Self {
+            width: grid_size,
+            height: grid_size,
+            depth: grid_size,
+            difficulty: Difficulty::default(),
+            obstacles: ObstacleLayout::None,
+            start: None,
+            food_script: Vec::new(),
+            target_score: None,
+            time_limit: None,
+            food_count: default_food_count(),
+            food_table: FoodTable::Classic,
+            prize_lifetime: Some(food::PRIZE_LIFETIME),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Start {
+    pub pos: Position,
+    pub heading: Direction,
 }
 
+/// How many turns can be buffered ahead of the snake's moves.
+pub const MAX_QUEUED_TURNS: usize = 3;
+
 pub struct Snake {
-    pub body: VecDeque<Position>,
+    body: VecDeque<Position>,
+    occupancy: Occupancy,
     pub direction: Direction,
-    pub next_direction: Direction,
+    /// Pending turns, applied one per move.
+    turns: VecDeque<Steer>,
 }
 
 impl Snake {
-    pub fn new(start_pos: Position, start_dir: Direction) -> Self {
-        let mut body = VecDeque::new();
-        body.push_back(start_pos);
-        // Add a couple more segments for initial length
-        // For simplicity, just one for now, or handle in Game::new
-        Self {
-            body,
+    pub fn new(start_pos: Position, start_dir: Direction, topology: &impl Topology) -> Self {
+        let mut snake = Self {
+            body: VecDeque::new(),
+            occupancy: Occupancy::new(topology),
             direction: start_dir,
-            next_direction: start_dir,
-        }
+            turns: VecDeque::new(),
+        };
+        // A new snake is just its head; it grows by eating
+        snake.push_back(start_pos);
+        snake
     }
 
     pub fn head(&self) -> Position {
         *self.body.front().unwrap()
     }
+
+    pub fn tail(&self) -> Position {
+        *self.body.back().unwrap()
+    }
+
+    /// Segments from head to tail. Grow and shrink through the push/pop
+    /// methods so the occupancy stays in sync.
+    pub fn body(&self) -> &VecDeque<Position> {
+        &self.body
+    }
+
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Buffers a turn for an upcoming move. Turns that would do nothing or
+    /// reverse into the neck, judged against the heading at the end of the
+    /// queue, are rejected.
+    pub fn queue_turn(&mut self, steer: Steer) -> bool {
+        let heading = self.turns.iter().fold(self.direction, |heading, steer| steer.resolve(heading));
+        let dir = steer.resolve(heading);
+        if self.turns.len() >= MAX_QUEUED_TURNS || dir == heading || dir == heading.opposite() {
+            return false;
+        }
+        self.turns.push_back(steer);
+        true
+    }
+
+    /// Applies the first still-valid queued turn, returning whether there was
+    /// one. An edge crossing may have rotated the heading since the turn was
+    /// queued, so relative turns are resolved here, and absolute ones dropped
+    /// if they have become a no-op or a reversal.
+    fn apply_next_turn(&mut self) -> bool {
+        while let Some(steer) = self.turns.pop_front() {
+            let dir = steer.resolve(self.direction);
+            if dir != self.direction && dir != self.direction.opposite() {
+                self.direction = dir;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn occupies(&self, pos: Position) -> bool {
+        self.occupancy.contains(pos)
+    }
+
+    pub fn push_front(&mut self, pos: Position) {
+        self.occupancy.insert(pos);
+        self.body.push_front(pos);
+    }
+
+    pub fn push_back(&mut self, pos: Position) {
+        self.occupancy.insert(pos);
+        self.body.push_back(pos);
+    }
+
+    pub fn pop_back(&mut self) -> Option<Position> {
+        let pos = self.body.pop_back()?;
+        self.occupancy.remove(pos);
+        Some(pos)
+    }
 }
 
-#[derive(PartialEq)]
+/// Summary of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameStats {
+    pub score: u32,
+    pub length: usize,
+    pub food_eaten: u32,
+    pub ticks: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// Ran off the edge of a walled surface.
+    Wall,
+    SelfCollision,
+    Obstacle,
+    /// The game's time limit ran out.
+    OutOfTime,
+}
+
+/// Something that happened during a tick. `update` reports them in the order
+/// they happened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameEvent {
-    None,
-    Eat,
-    EatPrize,
-    GameOver,
+    /// A queued turn was applied before the move.
+    Turned { from: Direction, to: Direction },
+    /// The head crossed an edge onto another face.
+    FaceEntered { from: Face, to: Face },
+    Ate { kind: FoodKind, pos: Position },
+    ScoreChanged { score: u32, gained: u32 },
+    Grew { length: usize },
+    /// A shrink item took segments off the tail.
+    Shrank { length: usize },
+    /// An expiring item has `ticks_left` moves left after this one; reported
+    /// every move once it's close.
+    FoodExpiring { kind: FoodKind, pos: Position, ticks_left: u64 },
+    /// An item ran out of time and left the board.
+    FoodExpired { kind: FoodKind, pos: Position },
+    /// A power-up started, or was extended, and now has `ticks_left` moves to run.
+    PowerUpStarted { power: PowerUp, ticks_left: u64 },
+    PowerUpExpired(PowerUp),
+    FoodSpawned { kind: FoodKind, pos: Position },
+    /// `pos` is the cell the snake died in or ran into.
+    Died { cause: DeathCause, pos: Position },
+    /// The snake covers every open cell of the surface.
+    Victory(GameStats),
+    /// The score reached the game's target.
+    TargetReached(GameStats),
 }
 
-pub struct GameState {
+pub struct GameState<T: Topology = Cube> {
     pub snake: Snake,
-    pub food: Position,
-    pub is_prize: bool,
+    /// Items on the board, oldest first.
+    pub foods: Vec<Food>,
+    /// Items spawned so far, eaten or not.
+    food_spawned: u32,
     pub score: u32,
     pub high_score: u32,
     pub food_eaten_count: u32,
+    /// Moves made so far.
+    pub ticks: u64,
+    /// Seconds of play so far, at the pace each move was made.
+    pub elapsed: f64,
+    /// Multiplier on the move interval from slow-down and speed-up items.
+    pub pace: f64,
+    /// Seconds added to the time limit by bonus-time items.
+    pub bonus_time: f64,
+    /// Power-ups currently running.
+    pub effects: Effects,
     pub game_over: bool,
+    /// Set when the snake covers every open cell, leaving nowhere to put
+    /// food, or reaches the target score.
+    pub won: bool,
     pub config: GameConfig,
+    /// Seed the game was started with; replaying it with the same inputs
+    /// reproduces the game exactly.
+    pub seed: u64,
+    rng: Rng,
+    pub topology: T,
+    /// Static walls; running into one ends the game.
+    pub obstacles: Vec<Position>,
+    blocked: Occupancy,
+    /// Every cell not taken by an obstacle.
+    cells: Vec<Position>,
+    /// Every accepted steering input, for replays.
+    input_log: Vec<TimedInput>,
 }
 
 impl GameState {
     pub fn new(grid_size: i32) -> Self {
-        let start_pos = Position {
-            face: Face::Front,
-            u: grid_size / 2,
-            v: grid_size / 2,
-        };
-        let snake = Snake::new(start_pos, Direction::Up);
+        Self::with_seed(grid_size, rng::entropy_seed())
+    }
+
+    pub fn with_seed(grid_size: i32, seed: u64) -> Self {
+        Self::with_config(GameConfig::cube(grid_size), seed)
+    }
+
+    pub fn with_config(config: GameConfig, seed: u64) -> Self {
+        let topology = Cube::cuboid(config.width, config.height, config.depth);
+        Self::with_topology(config, topology, seed)
+    }
+
+    /// Everything a save needs to pick the game up where it is.
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            config: self.config.clone(),
+            seed: self.seed,
+            rng: self.rng.clone(),
+            body: self.snake.body.iter().copied().collect(),
+            direction: self.snake.direction,
+            turns: self.snake.turns.iter().copied().collect(),
+            foods: self.foods.clone(),
+            food_spawned: self.food_spawned,
+            score: self.score,
+            high_score: self.high_score,
+            food_eaten_count: self.food_eaten_count,
+            ticks: self.ticks,
+            elapsed: self.elapsed,
+            pace: self.pace,
+            bonus_time: self.bonus_time,
+            effects: self.effects.clone(),
+            game_over: self.game_over,
+            won: self.won,
+            input_log: self.input_log.clone(),
+        }
+    }
+
+    /// Rebuilds a game from a snapshot, redoing the board and its walls from
+    /// the config and seed. Refuses snapshots with anything off that board,
+    /// since a save is just text anyone can edit.
+    pub(crate) fn restore(snapshot: Snapshot) -> Result<Self, String> {
+        let config = &snapshot.config;
+        if [config.width, config.height, config.depth].iter().any(|side| !(1..=MAX_VERIFIED_SIDE).contains(side)) {
+            return Err("board size out of range".into());
+        }
+        let mut game = Self::with_config(snapshot.config, snapshot.seed);
+        // Cells count their segments in a u16
+        if snapshot.body.is_empty() || snapshot.body.len() > u16::MAX as usize {
+            return Err("snake length out of range".into());
+        }
+        if !snapshot.body.iter().all(|&pos| game.topology.contains(pos)) {
+            return Err("snake is off the board".into());
+        }
+        if !snapshot.foods.iter().all(|food| game.topology.contains(food.pos) && food.spawned_at <= snapshot.ticks) {
+            return Err("food is off the board".into());
+        }
+        if snapshot.turns.len() > MAX_QUEUED_TURNS {
+            return Err("too many queued turns".into());
+        }
+        // NaN or out-of-range timing would make every move take no time at all
+        let (min_pace, max_pace) = food::PACE_RANGE;
+        if !(min_pace..=max_pace).contains(&snapshot.pace) {
+            return Err("pace out of range".into());
+        }
+        if !(snapshot.elapsed.is_finite() && snapshot.elapsed >= 0.0 && snapshot.bonus_time.is_finite() && snapshot.bonus_time >= 0.0) {
+            return Err("game time out of range".into());
+        }
+        if !snapshot.effects.is_possible_at(snapshot.ticks) {
+            return Err("power-ups out of range".into());
+        }
+
+        let mut snake = Snake::new(snapshot.body[0], snapshot.direction, &game.topology);
+        snapshot.body[1..].iter().for_each(|&pos| snake.push_back(pos));
+        snake.turns = snapshot.turns.into();
+        game.snake = snake;
+        game.foods = snapshot.foods;
+        game.rng = snapshot.rng;
+        game.food_spawned = snapshot.food_spawned;
+        game.score = snapshot.score;
+        game.high_score = snapshot.high_score;
+        game.food_eaten_count = snapshot.food_eaten_count;
+        game.ticks = snapshot.ticks;
+        game.elapsed = snapshot.elapsed;
+        game.pace = snapshot.pace;
+        game.bonus_time = snapshot.bonus_time;
+        game.effects = snapshot.effects;
+        game.game_over = snapshot.game_over;
+        game.won = snapshot.won;
+        game.input_log = snapshot.input_log;
+        Ok(game)
+    }
+
+    /// The game so far, as a replay that re-simulates it exactly.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            seed: self.seed,
+            config: self.config.clone(),
+            inputs: self.input_log.clone(),
+            ticks: self.ticks,
+        }
+    }
+}
+
+impl<T: Topology> GameState<T> {
+    pub fn with_topology(config: GameConfig, topology: T, seed: u64) -> Self {
+        let (width, height) = topology.extent(Face::Front);
+        let Start { pos: start_pos, heading } = config.start.filter(|start| topology.contains(start.pos)).unwrap_or(Start {
+            pos: Position { face: Face::Front, u: width / 2, v: height / 2 },
+            heading: Direction::Up,
+        });
+        let snake = Snake::new(start_pos, heading, &topology);
+        let mut rng = Rng::new(seed);
+        let keep_clear = obstacles::opening_run(&topology, start_pos, heading);
+        let obstacles = config.obstacles.place(&topology, &keep_clear, &mut rng);
+        let mut blocked = Occupancy::new(&topology);
+        for &pos in &obstacles {
+            blocked.insert(pos);
+        }
+        let cells = topology.cells().into_iter().filter(|&pos| !blocked.contains(pos)).collect();
         // Note: High score persistence would normally be loaded from localStorage here,
         // but accessing window/localStorage in pure logic struct is messy.
         // We'll handle it in lib.rs or pass it in.
@@ -88,367 +471,337 @@ impl GameState {
 
         let mut game = Self {
             snake,
-            food: start_pos, // Placeholder
-            is_prize: false,
+            foods: Vec::new(),
+            food_spawned: 0,
             score: 0,
             high_score: 0,
             food_eaten_count: 0,
+            ticks: 0,
+            elapsed: 0.0,
+            pace: 1.0,
+            bonus_time: 0.0,
+            effects: Effects::default(),
             game_over: false,
-            config: GameConfig { grid_size },
+            won: false,
+            config,
+            seed,
+            rng,
+            obstacles,
+            blocked,
+            cells,
+            topology,
+            input_log: Vec::new(),
         };
-        game.spawn_food();
+        game.refill_food();
         game
     }
 
-    pub fn spawn_food(&mut self) {
-        // Simple random spawn logic
-        // In a real game, ensure it doesn't spawn on snake
-        // Using a simple LCG or similar for determinism if needed, 
-        // but for now we'll rely on `getrandom` via a helper or just passed in entropy.
-        // Since we need `getrandom` which is available in WASM:
-        
-        let mut rng_buf = [0u8; 3];
-        getrandom::getrandom(&mut rng_buf).unwrap_or(());
-        
-        // Map bytes to face and UV
-        let face_idx = rng_buf[0] % 6;
-        let face = match face_idx {
-            0 => Face::Front,
-            1 => Face::Back,
-            2 => Face::Left,
-            3 => Face::Right,
-            4 => Face::Top,
-            _ => Face::Bottom,
-        };
-        let u = (rng_buf[1] as i32) % self.config.grid_size;
-        let v = (rng_buf[2] as i32) % self.config.grid_size;
-        
-        let new_pos = Position { face, u, v };
-        
-        // Check collision with snake
-        if self.snake.body.contains(&new_pos) {
-            self.spawn_food(); // Retry (recursive, but low probability of stack overflow for small snake)
-        } else {
-            self.food = new_pos;
-            // Spawn a prize every 5 items
-            self.is_prize = (self.food_eaten_count + 1) % 5 == 0;
+    /// Adds an item on a cell picked uniformly among the free ones.
+    /// Returns false if there's no free cell left.
+    pub fn spawn_food(&mut self) -> bool {
+        // Scripted food comes first, as long as its cell is free
+        let scripted = self.config.food_script.get(self.food_spawned as usize).copied();
+        if let Some(pos) = scripted.filter(|&p| self.topology.contains(p) && !self.blocked.contains(p) && self.is_free(p)) {
+            self.place_food(pos);
+            return true;
         }
+
+        // Every draw goes through the game's own RNG so a seed reproduces the food sequence.
+        // Blind draws that retry on the snake are uniform over free cells and cheap while
+        // the board is mostly empty; after a few misses, pick from the free cells directly.
+        let total = self.cells.len() as u32;
+        let mut pos = None;
+        for _ in 0..8 {
+            let candidate = self.cells[self.rng.below(total) as usize];
+            if self.is_free(candidate) {
+                pos = Some(candidate);
+                break;
+            }
+        }
+        if pos.is_none() {
+            let free: Vec<Position> = self.cells.iter().copied().filter(|&p| self.is_free(p)).collect();
+            if free.is_empty() {
+                return false;
+            }
+            pos = Some(free[self.rng.below(free.len() as u32) as usize]);
+        }
+
+        self.place_food(pos.unwrap());
+        true
+    }
+
+    fn place_food(&mut self, pos: Position) {
+        let kind = self.config.food_table.pick(self.food_spawned, &mut self.rng);
+        self.foods.push(Food { kind, pos, spawned_at: self.ticks });
+        self.food_spawned += 1;
     }
 
-    pub fn update(&mut self) -> GameEvent {
+    /// Tops the board up to the configured number of items, reporting each
+    /// new one. Stops early if the board runs out of room.
+    fn refill_food(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        while self.foods.len() < self.config.food_count.max(1) as usize && self.spawn_food() {
+            let food = self.foods[self.foods.len() - 1];
+            events.push(GameEvent::FoodSpawned { kind: food.kind, pos: food.pos });
+        }
+        events
+    }
+
+    fn is_free(&self, pos: Position) -> bool {
+        !self.snake.occupies(pos) && self.food_at(pos).is_none()
+    }
+
+    pub fn food_at(&self, pos: Position) -> Option<Food> {
+        self.foods.iter().copied().find(|food| food.pos == pos)
+    }
+
+    /// How many moves `food` stays for, if it doesn't stay forever.
+    pub fn food_lifetime(&self, food: &Food) -> Option<u32> {
+        match food.kind {
+            FoodKind::Prize => self.config.prize_lifetime,
+            _ => None,
+        }
+    }
+
+    /// Moves `food` has left after the current one, if it expires.
+    pub fn food_ticks_left(&self, food: &Food) -> Option<u64> {
+        let lifetime = self.food_lifetime(food)?;
+        Some((food.spawned_at + lifetime as u64).saturating_sub(self.ticks))
+    }
+
+    /// Removes items whose time is up and warns about those close to it.
+    fn expire_food(&mut self, events: &mut Vec<GameEvent>) {
+        // Taken out for the retain so it can still ask `self` for lifetimes
+        let mut foods = std::mem::take(&mut self.foods);
+        let before = foods.len();
+        foods.retain(|food| {
+            let Some(lifetime) = self.food_lifetime(food) else { return true };
+            let age = self.ticks - food.spawned_at;
+            if age > lifetime as u64 {
+                events.push(GameEvent::FoodExpired { kind: food.kind, pos: food.pos });
+                return false;
+            }
+            if lifetime as u64 - age < food::EXPIRY_WARNING_TICKS {
+                events.push(GameEvent::FoodExpiring { kind: food.kind, pos: food.pos, ticks_left: lifetime as u64 - age });
+            }
+            true
+        });
+        let expired = foods.len() < before;
+        self.foods = foods;
+        if expired {
+            let spawned = self.refill_food();
+            events.extend(spawned);
+        }
+    }
+
+    /// Seconds of game time left, for games with a time limit.
+    pub fn time_left(&self) -> Option<f64> {
+        self.config.time_limit.map(|limit| (limit + self.bonus_time - self.elapsed).max(0.0))
+    }
+
+    /// Advances one move and reports what happened, in order. Nothing happens
+    /// once the game is over.
+    pub fn update(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
         if self.game_over {
-            return GameEvent::None;
+            return events;
         }
 
-        self.snake.direction = self.snake.next_direction;
+        self.ticks += 1;
+        for power in self.effects.expire(self.ticks) {
+            events.push(GameEvent::PowerUpExpired(power));
+        }
+        self.expire_food(&mut events);
+        self.elapsed += self.move_interval();
+        if self.time_left() == Some(0.0) {
+            self.game_over = true;
+            events.push(GameEvent::Died { cause: DeathCause::OutOfTime, pos: self.snake.head() });
+            return events;
+        }
+        let heading = self.snake.direction;
+        if self.snake.apply_next_turn() {
+            events.push(GameEvent::Turned { from: heading, to: self.snake.direction });
+        }
         let head = self.snake.head();
-        let (new_pos, new_dir) = self.calculate_next_position(head, self.snake.direction);
+        let Some((new_pos, new_dir)) = self.calculate_next_position(head, self.snake.direction) else {
+            // Ran into the edge of a walled surface
+            self.game_over = true;
+            events.push(GameEvent::Died { cause: DeathCause::Wall, pos: head });
+            return events;
+        };
 
         // Check self collision
         // Note: Tail will move, so we shouldn't collide with tail unless length 2 reverses (impossible by rules)
         // But we check against current body minus tail if we don't grow.
         // Simplest: Check full body. If it's the tail, it's fine ONLY if we don't grow.
         
-        let growing = new_pos == self.food;
-        
-        if self.snake.body.contains(&new_pos) {
+        if self.blocked.contains(new_pos) {
+            self.game_over = true;
+            events.push(GameEvent::Died { cause: DeathCause::Obstacle, pos: new_pos });
+            return events;
+        }
+
+        let eaten = self.food_at(new_pos);
+        let growing = eaten.is_some_and(|food| food.kind.grows());
+
+        if self.snake.occupies(new_pos) {
             // If we are not growing, and new_pos is the tail, it's valid (chasing tail)
-            if !growing && new_pos == *self.snake.body.back().unwrap() {
+            if self.effects.is_active(PowerUp::Ghost) || (!growing && new_pos == self.snake.tail()) {
                 // Safe
             } else {
                 self.game_over = true;
-                return GameEvent::GameOver;
+                events.push(GameEvent::Died { cause: DeathCause::SelfCollision, pos: new_pos });
+                return events;
             }
         }
 
-        self.snake.body.push_front(new_pos);
+        self.snake.push_front(new_pos);
+        if new_pos.face != head.face {
+            events.push(GameEvent::FaceEntered { from: head.face, to: new_pos.face });
+        }
         // Update direction if changed by transition
         self.snake.direction = new_dir;
-        self.snake.next_direction = new_dir; // Lock it to avoid quick double turns messing up? 
-        // Actually, we should probably keep next_direction as user input buffer, 
-        // but if transition rotates us, we must update the current direction.
         
-        if growing {
-            self.score += if self.is_prize { 5 } else { 1 };
-            if self.score > self.high_score {
-                self.high_score = self.score;
+        if !growing {
+            self.snake.pop_back();
+        }
+        if let Some(food) = eaten {
+            self.foods.retain(|f| f.pos != food.pos);
+            self.eat(food, &mut events);
+            if self.config.target_score.is_some_and(|target| self.score >= target) {
+                self.won = true;
+                self.game_over = true;
+                events.push(GameEvent::TargetReached(self.stats()));
+                return events;
             }
-            self.food_eaten_count += 1;
-            let event = if self.is_prize { GameEvent::EatPrize } else { GameEvent::Eat };
-            self.spawn_food();
-            event
-        } else {
-            self.snake.body.pop_back();
-            GameEvent::None
-        }
-    }
-
-    fn calculate_next_position(&self, pos: Position, dir: Direction) -> (Position, Direction) {
-        let n = self.config.grid_size;
-        let mut u = pos.u;
-        let mut v = pos.v;
-        let mut face = pos.face;
-        let mut new_dir = dir;
-
-        match dir {
-            Direction::Up => v += 1,
-            Direction::Down => v -= 1,
-            Direction::Left => u -= 1,
-            Direction::Right => u += 1,
-        }
-
-        // Check bounds and transition
-        if u < 0 || u >= n || v < 0 || v >= n {
-            // Transition logic
-            match (face, dir) {
-                // Front Transitions
-                (Face::Front, Direction::Up) => { face = Face::Top; v = 0; } // Enter Bottom of Top
-                (Face::Front, Direction::Down) => { face = Face::Bottom; v = n - 1; } // Enter Top of Bottom
-                (Face::Front, Direction::Left) => { face = Face::Left; u = n - 1; } // Enter Right of Left
-                (Face::Front, Direction::Right) => { face = Face::Right; u = 0; } // Enter Left of Right
-
-                // Back Transitions
-                (Face::Back, Direction::Up) => { face = Face::Top; v = n - 1; new_dir = Direction::Down; u = n - 1 - u; } // Top of Back connects to Top of Top? 
-                // Let's define the unfolding.
-                // Standard box unfolding:
-                //   T
-                // L F R B
-                //   Bo
-                
-                // Front (F): u=R, v=U
-                // Top (T): u=R, v=B (Back) -> No, let's keep "Up" consistent with visual up if possible?
-                // If we fold T up 90 deg from F:
-                // F's top edge matches T's bottom edge.
-                // So F(Up) -> T(Up, entering at v=0). Correct.
-                
-                // Right (R):
-                // F's right edge matches R's left edge.
-                // F(Right) -> R(Right, entering at u=0). Correct.
-                
-                // Left (L):
-                // F's left edge matches L's right edge.
-                // F(Left) -> L(Left, entering at u=N-1). Correct.
-                
-                // Bottom (Bo):
-                // F's bottom edge matches Bo's top edge.
-                // F(Down) -> Bo(Down, entering at v=N-1). Correct.
-                
-                // Now the secondary connections (e.g. T -> R).
-                // T is above F. R is right of F.
-                // T's right edge should match R's top edge?
-                // Let's trace:
-                // F(N, N) is top-right corner.
-                // T(N, 0) is bottom-right corner of T.
-                // R(0, N) is top-left corner of R.
-                // So T(Right) -> R(Down)? Or R(Left)?
-                // Let's visualize the corner.
-                // Moving Right on T (increasing u) goes towards the corner shared by F, T, R.
-                // Crossing that edge goes onto R.
-                // On R, we are at the top edge (v=N-1).
-                // And we are moving "Down" (decreasing v) into R?
-                // Or are we moving "Right" relative to R?
-                // If R's "Up" is aligned with F's "Up", then R's top edge is shared with T's right edge.
-                // So T(Right) -> R(Down)?
-                // Let's assume T(Right) enters R at Top edge (v=N-1).
-                // Coordinate mapping:
-                // T(u=N, v) -> R(u=v?, v=N-1)?
-                // Let's trace the corner vertex.
-                // T(N,0) (bottom-right of T) touches F(N,N) (top-right of F) and R(0,N) (top-left of R).
-                // So T(Right) at v=0 enters R at u=0, v=N-1?
-                // Wait, T(Right) at v=0 is the corner.
-                // T(Right) generally means u goes N.
-                // If we cross u=N on T, we enter R.
-                // The edge is T's right edge.
-                // R's corresponding edge is R's Top edge.
-                // So T(Right) -> R(Down).
-                // Mapping: T(v) maps to R(u)?
-                // T(N, 0) -> R(0, N-1).
-                // T(N, N-1) -> R(N-1, N-1).
-                // So T(Right) -> R(Down). New u = T.v. New v = N-1.
-                
-                (Face::Top, Direction::Right) => { face = Face::Right; u = v; v = n - 1; new_dir = Direction::Down; }
-                (Face::Top, Direction::Left) => { face = Face::Left; u = n - 1 - v; v = n - 1; new_dir = Direction::Down; }
-                
-                (Face::Top, Direction::Up) => { face = Face::Back; v = n - 1; new_dir = Direction::Down; u = n - 1 - u; } // Top of T connects to Top of B.
-                // B is back of F.
-                // T(Up) -> B.
-                // If we go "Up" on T (away from F), we go over the back to B.
-                // B's orientation: usually "Up" on B is same global Up?
-                // If so, T's top edge touches B's top edge.
-                // So T(Up) -> B(Down).
-                // T(u) maps to B(u)?
-                // T(0, N) (top-left of T) -> B(N, N) (top-right of B)?
-                // Let's assume B is "unwrapped" such that B's right is L, B's left is R.
-                // Standard T-shape map:
-                //   T
-                // L F R
-                //   Bo
-                //   B (below Bo)
-                // OR
-                //   T
-                // L F R B (strip)
-                //   Bo
-                // Let's stick to:
-                // F neighbors: T(U), Bo(D), L(L), R(R).
-                // B neighbors: T(U), Bo(D), R(L), L(R). (Wrapped around).
-                // Note: B's Left is R (because R wraps around). B's Right is L.
-                // B's Up is T?
-                // If F(Up)->T(Bottom), then T(Top)->B(Top)? Yes.
-                // So T(Up) -> B(Down).
-                // Coordinate matching:
-                // T(u) goes 0..N (Left to Right).
-                // B(u) goes 0..N (Left to Right, looking at B).
-                // If we go over the top:
-                // T(0, N) is T-Left-Top corner.
-                // B(0, N) is B-Left-Top corner?
-                // If B is viewed from behind, "Left" is actually World Right?
-                // Let's define B's u,v such that B(u,v) corresponds to the face rendering.
-                // If we look at B, u increases Right, v increases Up.
-                // T(Top-Left) connects to B(Top-Left)?
-                // No, T(Top-Left) is World-Left-Back.
-                // B(Top-Left) is World-Right-Back (if looking at B from back).
-                // Wait, "Left" on B (u=0) should be adjacent to R?
-                // Let's check the strip: L - F - R - B - L ...
-                // F(Right) -> R(Left).
-                // R(Right) -> B(Left).
-                // B(Right) -> L(Left).
-                // L(Right) -> F(Left).
-                // So B's Left edge (u=0) is R's Right edge (u=N).
-                // B's Right edge (u=N) is L's Left edge (u=0).
-                // B's Top edge (v=N) is T's Top edge (v=N).
-                // B's Bottom edge (v=0) is Bo's Bottom edge (v=0).
-                
-                // So T(Up) -> B(Down).
-                // T(u) matches B(u) but inverted?
-                // T(Left) is L side. T(Right) is R side.
-                // B(Left) is R side. B(Right) is L side.
-                // So T(Left) connects to B(Right)? No.
-                // T(Left) connects to L.
-                // T(Right) connects to R.
-                // T(Top) connects to B(Top).
-                // T(Top-Left) is corner L-T-B.
-                // B(Top-Right) is corner L-T-B (since B(Right) is L).
-                // So T(u=0) -> B(u=N-1).
-                // T(u=N-1) -> B(u=0).
-                // So u = N - 1 - u.
-                
-                (Face::Top, Direction::Down) => { face = Face::Front; v = n - 1; } // Back to Front
-
-                // Bottom Transitions
-                (Face::Bottom, Direction::Up) => { face = Face::Front; v = 0; }
-                (Face::Bottom, Direction::Down) => { face = Face::Back; v = 0; new_dir = Direction::Up; u = n - 1 - u; } // Bo(Bottom) -> B(Bottom).
-                // B(Bottom) is v=0.
-                // Bo(Bottom) is v=0.
-                // Bo(Down) -> B(Up).
-                // Matching u:
-                // Bo(Left) is L. Bo(Right) is R.
-                // B(Left) is R. B(Right) is L.
-                // Bo(Left-Bottom) corner L-Bo-B.
-                // B(Right-Bottom) corner L-Bo-B.
-                // So Bo(u=0) -> B(u=N-1).
-                // u = N - 1 - u.
-                
-                (Face::Bottom, Direction::Left) => { face = Face::Left; u = v; v = 0; new_dir = Direction::Up; } // Bo(Left) -> L(Bottom).
-                // Bo(Left) is u=-1.
-                // L(Bottom) is v=0.
-                // Bo(Left) edge matches L(Bottom) edge.
-                // Bo(u=0, v) -> L(u=?, v=0).
-                // Bo(0, 0) (Left-Bottom) -> L(N-1, 0) (Right-Bottom)?
-                // Bo(0, N-1) (Left-Top) -> L(0, 0) (Left-Bottom)?
-                // Wait, Bo(Top) is F. L(Bottom) is Bo.
-                // L(Right) is F.
-                // So L(Bottom-Right) is F-Bo-L corner.
-                // Bo(Top-Left) is F-Bo-L corner.
-                // So Bo(0, N-1) -> L(N-1, 0).
-                // Bo(0, 0) -> L(0, 0)?
-                // Let's check Bo(Bottom) -> B.
-                // L(Left) -> B.
-                // So L(Bottom-Left) is B-Bo-L corner.
-                // Bo(Bottom-Left) is B-Bo-L corner.
-                // So Bo(0, 0) -> L(0, 0).
-                // So Bo(Left) -> L(Up).
-                // Bo.v maps to L.u.
-                // Bo(v=0) -> L(u=0).
-                // Bo(v=N-1) -> L(u=N-1).
-                // So u = v.
-                
-                (Face::Bottom, Direction::Right) => { face = Face::Right; u = n - 1 - v; v = 0; new_dir = Direction::Up; }
-                // Bo(Right) -> R(Bottom).
-                // Bo(Right) is u=N.
-                // R(Bottom) is v=0.
-                // Bo(N, N-1) (Top-Right) -> R(0, 0) (Left-Bottom).
-                // Bo(N, 0) (Bottom-Right) -> R(N-1, 0) (Right-Bottom).
-                // So Bo.v maps to R.u inverted.
-                // u = N - 1 - v.
-
-                // Right Transitions
-                (Face::Right, Direction::Left) => { face = Face::Front; u = n - 1; }
-                (Face::Right, Direction::Right) => { face = Face::Back; u = 0; } // R(Right) -> B(Left).
-
-                // Wait, R(Up) is v=N.
-                // T(Right) is u=N.
-                // R(Top-Left) -> T(Bottom-Right)?
-                // R(0, N) -> T(N, 0).
-                // R(N-1, N) -> T(N, N-1).
-                // So R.u maps to T.v.
-                // u = R.u. v = R.u.
-                // Wait, R(Up) -> T(Right).
-                // We enter T from the Right edge (u=N).
-                // So we are moving Left on T.
-                // new_dir = Left.
-                // u = N - 1.
-                // v = ?.
-                // R(0, N) -> T(N, 0). (u=0 -> v=0).
-                // R(N-1, N) -> T(N, N-1). (u=N-1 -> v=N-1).
-                // So v = u (old u).
-                (Face::Right, Direction::Up) => { face = Face::Top; let old_u = u; u = n - 1; v = old_u; new_dir = Direction::Left; }
-
-                (Face::Right, Direction::Down) => { face = Face::Bottom; let old_u = u; u = n - 1; v = n - 1 - old_u; new_dir = Direction::Left; }
-                // R(Down) -> Bo(Right).
-                // R(Bottom-Left) -> Bo(Top-Right).
-                // R(0, 0) -> Bo(N, N-1).
-                // R(N-1, 0) -> Bo(N, 0).
-                // So R.u maps to Bo.v inverted.
-                // v = N - 1 - u.
-                // Enter Bo from Right (u=N). Moving Left.
-                
-                // Left Transitions
-                (Face::Left, Direction::Right) => { face = Face::Front; u = 0; }
-                (Face::Left, Direction::Left) => { face = Face::Back; u = n - 1; } // L(Left) -> B(Right).
-                (Face::Left, Direction::Up) => { face = Face::Top; let old_u = u; u = 0; v = n - 1 - old_u; new_dir = Direction::Right; }
-                // L(Up) -> T(Left).
-                // L(Top-Right) -> T(Bottom-Left).
-                // L(N-1, N) -> T(0, 0).
-                // L(0, N) -> T(0, N-1).
-                // So L.u maps to T.v inverted.
-                // v = N - 1 - u.
-                // Enter T from Left (u=-1). Moving Right.
-                
-                (Face::Left, Direction::Down) => { face = Face::Bottom; let old_u = u; u = 0; v = old_u; new_dir = Direction::Right; }
-                // L(Down) -> Bo(Left).
-                // L(Bottom-Right) -> Bo(Top-Left).
-                // L(N-1, 0) -> Bo(0, N-1).
-                // L(0, 0) -> Bo(0, 0).
-                // So L.u maps to Bo.v.
-                // v = u.
-                // Enter Bo from Left (u=-1). Moving Right.
-                
-                // Back Transitions
-                (Face::Back, Direction::Down) => { face = Face::Bottom; v = 0; new_dir = Direction::Up; u = n - 1 - u; } // B(Down) -> Bo(Bottom) ??
-                // Wait, B(Down) is v=-1.
-                // B(Top) is T. B(Bottom) is Bo.
-                // B(Bottom) edge matches Bo(Bottom) edge.
-                // So B(Down) -> Bo(Up).
-                // u = N - 1 - u.
-                
-                (Face::Back, Direction::Left) => { face = Face::Right; u = n - 1; } // B(Left) -> R(Right).
-                (Face::Back, Direction::Right) => { face = Face::Left; u = 0; } // B(Right) -> L(Left).
-                
+            let spawned = self.refill_food();
+            if self.foods.is_empty() {
+                // Nowhere left to put food: the snake covers every cell. Its
+                // length alone can't tell, since a ghosted body can overlap itself
+                self.won = true;
+                self.game_over = true;
+                events.push(GameEvent::Victory(self.stats()));
+                return events;
+            }
+            events.extend(spawned);
+        }
+        if self.effects.is_active(PowerUp::Magnet) {
+            self.pull_food();
+        }
+        events
+    }
 
+    /// Moves each item within reach on the head's face one cell towards the
+    /// head, along whichever axis it's further out on, if that cell is free.
+    fn pull_food(&mut self) {
+        let head = self.snake.head();
+        for i in 0..self.foods.len() {
+            let pos = self.foods[i].pos;
+            let (du, dv) = (head.u - pos.u, head.v - pos.v);
+            let distance = du.abs() + dv.abs();
+            if pos.face != head.face || distance <= 1 || distance > powerups::MAGNET_RANGE {
+                continue;
+            }
+            let next = if du.abs() >= dv.abs() {
+                Position { u: pos.u + du.signum(), ..pos }
+            } else {
+                Position { v: pos.v + dv.signum(), ..pos }
+            };
+            if !self.blocked.contains(next) && self.is_free(next) {
+                self.foods[i].pos = next;
+            }
+        }
+    }
+
+    /// Scores `food` and applies its effect. The head is already on its cell.
+    fn eat(&mut self, food: Food, events: &mut Vec<GameEvent>) {
+        let multiplier = if self.effects.is_active(PowerUp::DoubleScore) { 2 } else { 1 };
+        let points = match self.food_lifetime(&food) {
+            Some(lifetime) => food::decayed_points(food.kind.points(), self.ticks - food.spawned_at, lifetime),
+            None => food.kind.points(),
+        };
+        let gained = points * multiplier;
+        self.score += gained;
+        if self.score > self.high_score {
+            self.high_score = self.score;
+        }
+        self.food_eaten_count += 1;
+        events.push(GameEvent::Ate { kind: food.kind, pos: food.pos });
+        events.push(GameEvent::ScoreChanged { score: self.score, gained });
+
+        let (min_pace, max_pace) = food::PACE_RANGE;
+        match food.kind {
+            FoodKind::Normal | FoodKind::Prize => {}
+            FoodKind::Shrink => {
+                for _ in 0..food::SHRINK_SEGMENTS {
+                    if self.snake.len() <= 1 {
+                        break;
+                    }
+                    self.snake.pop_back();
+                }
+                events.push(GameEvent::Shrank { length: self.snake.len() });
+            }
+            FoodKind::SlowDown => self.pace = (self.pace * food::PACE_STEP).min(max_pace),
+            FoodKind::SpeedUp => self.pace = (self.pace / food::PACE_STEP).max(min_pace),
+            FoodKind::BonusTime => {
+                if self.config.time_limit.is_some() {
+                    self.bonus_time += food::BONUS_SECONDS;
+                }
             }
+            FoodKind::PowerUp(power) => {
+                let ticks_left = self.effects.grant(power, self.ticks);
+                events.push(GameEvent::PowerUpStarted { power, ticks_left });
+            }
+        }
+        if food.kind.grows() {
+            events.push(GameEvent::Grew { length: self.snake.len() });
+        }
+    }
+
+    /// Queues a steering input for an upcoming move, logging it for replays if accepted.
+    pub fn steer(&mut self, steer: Steer) -> bool {
+        let accepted = !self.game_over && self.snake.queue_turn(steer);
+        if accepted {
+            self.input_log.push(TimedInput { tick: self.ticks, steer });
+        }
+        accepted
+    }
+
+    pub fn queue_turn(&mut self, dir: Direction) -> bool {
+        self.steer(Steer::Absolute(dir))
+    }
+
+    pub fn queue_relative_turn(&mut self, turn: Turn) -> bool {
+        self.steer(Steer::Relative(turn))
+    }
+
+    pub fn stats(&self) -> GameStats {
+        GameStats {
+            score: self.score,
+            length: self.snake.len(),
+            food_eaten: self.food_eaten_count,
+            ticks: self.ticks,
         }
+    }
+
+    pub fn is_blocked(&self, pos: Position) -> bool {
+        self.blocked.contains(pos)
+    }
 
-        (Position { face, u, v }, new_dir)
+    /// Seconds between moves at the game's current pace.
+    pub fn move_interval(&self) -> f64 {
+        let curve = self.config.difficulty.curve();
+        let progress = match curve.driver {
+            SpeedDriver::Score => self.score as f64,
+            SpeedDriver::Length => self.snake.len() as f64,
+            SpeedDriver::Time => self.elapsed,
+        };
+        let slow_mo = if self.effects.is_active(PowerUp::SlowMo) { powerups::SLOW_MO_FACTOR } else { 1.0 };
+        (curve.interval(progress) * self.pace * slow_mo).max(MIN_MOVE_INTERVAL)
+    }
+
+    fn calculate_next_position(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)> {
+        self.topology.step(pos, dir)
     }
 }
 
@@ -463,14 +816,14 @@ mod tests {
         
         // Front -> Top
         let pos = Position { face: Face::Front, u: 5, v: 15 };
-        let (new_pos, _) = game.calculate_next_position(pos, Direction::Up);
+        let (new_pos, _) = game.calculate_next_position(pos, Direction::Up).unwrap();
         assert_eq!(new_pos.face, Face::Top);
         assert_eq!(new_pos.u, 5);
         assert_eq!(new_pos.v, 0);
 
         // Front -> Right
         let pos = Position { face: Face::Front, u: 15, v: 5 };
-        let (new_pos, _) = game.calculate_next_position(pos, Direction::Right);
+        let (new_pos, _) = game.calculate_next_position(pos, Direction::Right).unwrap();
         assert_eq!(new_pos.face, Face::Right);
         assert_eq!(new_pos.u, 0);
         assert_eq!(new_pos.v, 5);
@@ -483,10 +836,315 @@ mod tests {
 
         // Top -> Back (Up)
         let pos = Position { face: Face::Top, u: 5, v: 15 };
-        let (new_pos, new_dir) = game.calculate_next_position(pos, Direction::Up);
+        let (new_pos, new_dir) = game.calculate_next_position(pos, Direction::Up).unwrap();
         assert_eq!(new_pos.face, Face::Back);
         assert_eq!(new_dir, Direction::Down);
         assert_eq!(new_pos.u, 16 - 1 - 5); // 10
         assert_eq!(new_pos.v, 15);
     }
+
+    #[test]
+    fn test_same_seed_same_food_sequence() {
+        let mut a = GameState::with_seed(10, 42);
+        let mut b = GameState::with_seed(10, 42);
+        for _ in 0..20 {
+            assert_eq!(a.foods, b.foods);
+            a.food_eaten_count += 1;
+            b.food_eaten_count += 1;
+            a.spawn_food();
+            b.spawn_food();
+        }
+    }
+
+    #[test]
+    fn test_walled_surface_ends_game_at_edge() {
+        use crate::topology::Walled;
+
+        let mut game = GameState::with_topology(GameConfig::cube(4), Walled { width: 4, height: 4 }, 7);
+        // Start is (2, 2) heading Up, so the second step leaves the board
+        assert!(game.update().is_empty());
+        let start = game.snake.head();
+        assert_eq!(game.update(), vec![GameEvent::Died { cause: DeathCause::Wall, pos: start }]);
+        assert!(game.game_over);
+    }
+
+    #[test]
+    fn test_update_reports_events_in_order() {
+        let mut game = GameState::with_seed(4, 11);
+        let start = Position { face: Face::Front, u: 1, v: 3 };
+        game.snake = Snake::new(start, Direction::Right, &game.topology);
+        let (food, _) = game.topology.step(start, Direction::Up).unwrap();
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: food, spawned_at: 0 }];
+
+        assert!(game.queue_turn(Direction::Up));
+        let events = game.update();
+        assert_eq!(
+            events[..5],
+            [
+                GameEvent::Turned { from: Direction::Right, to: Direction::Up },
+                GameEvent::FaceEntered { from: Face::Front, to: Face::Top },
+                GameEvent::Ate { kind: FoodKind::Normal, pos: food },
+                GameEvent::ScoreChanged { score: 1, gained: 1 },
+                GameEvent::Grew { length: 2 },
+            ]
+        );
+        assert!(matches!(events[5], GameEvent::FoodSpawned { pos, .. } if pos == game.foods[0].pos));
+        assert_eq!(events.len(), 6);
+    }
+
+    #[test]
+    fn test_obstacles_kill_and_stay_free_of_food() {
+        use crate::obstacles::ObstacleLayout;
+
+        let wall = Position { face: Face::Front, u: 5, v: 9 };
+        let config = GameConfig { obstacles: ObstacleLayout::Cells(vec![wall]), ..GameConfig::cube(10) };
+        let mut game = GameState::with_config(config, 4);
+        assert_eq!(game.obstacles, vec![wall]);
+        for _ in 0..3 {
+            assert!(!game.update().iter().any(|e| matches!(e, GameEvent::Died { .. })));
+        }
+        assert_eq!(game.update().last(), Some(&GameEvent::Died { cause: DeathCause::Obstacle, pos: wall }));
+
+        // A 2-cube packed with walls leaves food only the open cells
+        let config = GameConfig { obstacles: ObstacleLayout::Random { count: 100 }, ..GameConfig::cube(2) };
+        let mut game = GameState::with_config(config, 8);
+        assert_eq!(game.obstacles.len(), 24 - 5);
+        for _ in 0..20 {
+            game.foods.clear();
+            assert!(game.spawn_food());
+            assert!(!game.is_blocked(game.foods[0].pos));
+        }
+    }
+
+    #[test]
+    fn test_several_items_on_board_with_their_own_effects() {
+        let config = GameConfig { food_count: 3, time_limit: Some(30.0), ..GameConfig::cube(10) };
+        let mut game = GameState::with_config(config, 6);
+        assert_eq!(game.foods.len(), 3);
+
+        // Start is (5, 5) heading Up, with a tail trailing below it
+        for v in (1..5).rev() {
+            game.snake.push_back(Position { face: Face::Front, u: 5, v });
+        }
+        let ahead = |v| Position { face: Face::Front, u: 5, v };
+        game.foods = vec![
+            Food { kind: FoodKind::Shrink, pos: ahead(6), spawned_at: 0 },
+            Food { kind: FoodKind::SlowDown, pos: ahead(7), spawned_at: 0 },
+            Food { kind: FoodKind::BonusTime, pos: ahead(8), spawned_at: 0 },
+        ];
+
+        let events = game.update();
+        assert_eq!(events[2], GameEvent::Shrank { length: 2 });
+        assert!(matches!(events[3], GameEvent::FoodSpawned { .. }));
+        assert_eq!(game.foods.len(), 3);
+
+        let before = game.move_interval();
+        game.update();
+        assert_eq!(game.pace, food::PACE_STEP);
+        assert!(game.move_interval() > before);
+
+        game.update();
+        assert_eq!(game.time_left(), Some(30.0 + food::BONUS_SECONDS - game.elapsed));
+        assert_eq!(game.score, 3);
+    }
+
+    #[test]
+    fn test_power_ups_apply_then_expire_on_schedule() {
+        let cell = |u, v| Position { face: Face::Front, u, v };
+        let mut game = GameState::with_seed(10, 3);
+        // Head at (5, 5) heading Up, with the body curled round into its path
+        for (u, v) in [(6, 5), (6, 6), (5, 6), (4, 6)] {
+            game.snake.push_back(cell(u, v));
+        }
+        game.effects.grant(PowerUp::Ghost, game.ticks);
+        game.foods = vec![
+            Food { kind: FoodKind::PowerUp(PowerUp::DoubleScore), pos: cell(5, 7), spawned_at: 0 },
+            Food { kind: FoodKind::Normal, pos: cell(5, 8), spawned_at: 0 },
+        ];
+
+        assert!(!game.update().iter().any(|e| matches!(e, GameEvent::Died { .. })), "ghosts pass through");
+        assert!(game.update().contains(&GameEvent::PowerUpStarted { power: PowerUp::DoubleScore, ticks_left: 50 }));
+        assert!(game.update().contains(&GameEvent::ScoreChanged { score: 3, gained: 2 }));
+
+        while game.ticks < PowerUp::Ghost.duration() {
+            assert!(!game.update().contains(&GameEvent::PowerUpExpired(PowerUp::Ghost)));
+        }
+        assert_eq!(game.update()[0], GameEvent::PowerUpExpired(PowerUp::Ghost));
+        assert!(game.effects.is_active(PowerUp::DoubleScore));
+
+        // A magnet draws food in along the face
+        let mut game = GameState::with_seed(10, 1);
+        game.effects.grant(PowerUp::Magnet, game.ticks);
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: cell(8, 6), spawned_at: 0 }];
+        game.update();
+        assert_eq!(game.foods[0].pos, cell(7, 6));
+    }
+
+    #[test]
+    fn test_prizes_expire_and_lose_points_while_waiting() {
+        let mut game = GameState::with_seed(10, 2);
+        let lifetime = game.config.prize_lifetime.unwrap() as u64;
+        // Off the snake's straight run round the cube
+        let far = Position { face: Face::Back, u: 0, v: 0 };
+        game.foods = vec![Food { kind: FoodKind::Prize, pos: far, spawned_at: 0 }];
+        let mut warnings = 0;
+        while game.ticks < lifetime {
+            let events = game.update();
+            warnings += events.iter().filter(|e| matches!(e, GameEvent::FoodExpiring { .. })).count() as u64;
+        }
+        assert_eq!(warnings, food::EXPIRY_WARNING_TICKS);
+        let events = game.update();
+        assert_eq!(events[0], GameEvent::FoodExpired { kind: FoodKind::Prize, pos: far });
+        assert!(matches!(events[1], GameEvent::FoodSpawned { .. }));
+        assert!(game.food_at(far).is_none());
+
+        // Eaten on the 32nd move of its 60, a 5-point prize is worth 3
+        let mut game = GameState::with_seed(10, 2);
+        game.ticks = 30;
+        let ahead = Position { face: Face::Front, u: 5, v: 7 };
+        game.foods = vec![Food { kind: FoodKind::Prize, pos: ahead, spawned_at: 0 }];
+        game.update();
+        assert!(game.update().contains(&GameEvent::ScoreChanged { score: 3, gained: 3 }));
+    }
+
+    #[test]
+    fn test_occupancy_follows_body() {
+        let cube = Cube::new(4);
+        let start = Position { face: Face::Front, u: 0, v: 0 };
+        let mut snake = Snake::new(start, Direction::Up, &cube);
+        let next = Position { face: Face::Front, u: 0, v: 1 };
+        snake.push_front(next);
+        assert!(snake.occupies(start) && snake.occupies(next));
+        snake.pop_back();
+        assert!(!snake.occupies(start) && snake.occupies(next));
+
+        // Chasing the tail: head and tail share a cell for a moment
+        snake.push_front(next);
+        snake.pop_back();
+        assert!(snake.occupies(next));
+    }
+
+    #[test]
+    fn test_spawn_food_finds_last_free_cell_then_reports_full_board() {
+        let mut game = GameState::with_seed(2, 3);
+        let cells = game.topology.cells();
+        let last = *cells.iter().rev().find(|&&p| p != game.snake.head()).unwrap();
+        for &pos in &cells {
+            if pos != last && pos != game.snake.head() {
+                game.snake.push_back(pos);
+            }
+        }
+        game.foods.clear();
+        assert!(game.spawn_food());
+        assert_eq!(game.foods[0].pos, last);
+
+        game.snake.push_back(last);
+        assert!(!game.spawn_food());
+    }
+
+    #[test]
+    fn test_filling_the_surface_is_a_victory() {
+        let mut game = GameState::with_seed(1, 5);
+        // One-cell faces: point the head at the food, with every other cell already taken
+        let head = game.snake.head();
+        let (food, _) = game.topology.step(head, Direction::Up).unwrap();
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: food, spawned_at: 0 }];
+        game.snake.direction = Direction::Up;
+        for pos in game.topology.cells() {
+            if pos != head && pos != food {
+                game.snake.push_back(pos);
+            }
+        }
+
+        let events = game.update();
+        assert!(events.contains(&GameEvent::Ate { kind: FoodKind::Normal, pos: food }));
+        assert!(matches!(events.last(), Some(GameEvent::Victory(GameStats { length: 6, ticks: 1, .. }))));
+        assert!(game.won && game.game_over);
+    }
+
+    #[test]
+    fn test_overlapping_ghost_body_does_not_fill_the_surface() {
+        let mut game = GameState::with_seed(1, 5);
+        game.effects.grant(PowerUp::Ghost, game.ticks);
+        let head = game.snake.head();
+        let (food, _) = game.topology.step(head, Direction::Up).unwrap();
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: food, spawned_at: 0 }];
+        game.snake.direction = Direction::Up;
+        // Long enough to cover the surface once grown, but doubled up on its tail, leaving a cell empty
+        let mut others = game.topology.cells().into_iter().filter(|&pos| pos != head && pos != food);
+        let empty = others.next().unwrap();
+        for pos in others {
+            game.snake.push_back(pos);
+        }
+        game.snake.push_back(game.snake.tail());
+        assert_eq!(game.snake.len() + 1, game.cells.len());
+
+        let events = game.update();
+        assert!(events.contains(&GameEvent::Ate { kind: FoodKind::Normal, pos: food }));
+        assert!(!game.won && !game.game_over);
+        assert_eq!(game.foods[0].pos, empty);
+    }
+
+    #[test]
+    fn test_quick_turns_are_all_applied() {
+        let mut game = GameState::with_seed(10, 1);
+        game.snake.direction = Direction::Right;
+        // U-turn: both presses land before the next move
+        assert!(game.queue_turn(Direction::Up));
+        assert!(game.queue_turn(Direction::Left));
+        game.update();
+        assert_eq!(game.snake.direction, Direction::Up);
+        game.update();
+        assert_eq!(game.snake.direction, Direction::Left);
+    }
+
+    #[test]
+    fn test_reversal_is_rejected() {
+        let mut game = GameState::with_seed(10, 1);
+        assert!(!game.queue_turn(Direction::Down));
+        assert!(game.queue_turn(Direction::Left));
+        // Reversal of the queued turn, not of the current heading
+        assert!(!game.queue_turn(Direction::Right));
+        assert!(game.queue_turn(Direction::Down));
+    }
+
+    #[test]
+    fn test_queued_turn_rechecked_after_edge_rotates_heading() {
+        let mut game = GameState::with_seed(4, 1);
+        // Turning Right at Top's right edge crosses onto Right heading Down
+        let start = Position { face: Face::Top, u: 3, v: 1 };
+        game.snake.pop_back();
+        game.snake.push_back(start);
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: Position { face: Face::Bottom, u: 0, v: 0 }, spawned_at: 0 }];
+        assert!(game.queue_turn(Direction::Right));
+        assert!(game.queue_turn(Direction::Up));
+        game.update();
+        assert_eq!(game.snake.head(), Position { face: Face::Right, u: 1, v: 3 });
+        assert_eq!(game.snake.direction, Direction::Down);
+        // Up was fine after Right, but would now reverse into the neck
+        game.update();
+        assert_eq!(game.snake.direction, Direction::Down);
+        assert!(!game.game_over);
+    }
+
+    #[test]
+    fn test_relative_turns_follow_rotated_heading() {
+        let mut game = GameState::with_seed(4, 1);
+        // Heading Up along Right's left column, about to cross onto Top heading Left
+        let start = Position { face: Face::Right, u: 1, v: 3 };
+        game.snake.pop_back();
+        game.snake.push_back(start);
+        game.foods = vec![Food { kind: FoodKind::Normal, pos: Position { face: Face::Bottom, u: 0, v: 0 }, spawned_at: 0 }];
+        game.update();
+        assert_eq!(game.snake.head().face, Face::Top);
+        assert_eq!(game.snake.direction, Direction::Left);
+
+        // Turns are resolved against the rotated heading
+        assert!(game.queue_relative_turn(Turn::Left));
+        assert!(game.queue_relative_turn(Turn::Right));
+        game.update();
+        assert_eq!(game.snake.direction, Direction::Down);
+        game.update();
+        assert_eq!(game.snake.direction, Direction::Left);
+    }
 }
@@ -0,0 +1,227 @@
+use crate::game::{Direction, Face, Position};
+use crate::topology::Cube;
+
+/// World-space size of one cell. Cells are square, so the longest side of
+/// the box spans -1..1.
+pub fn cell_size(cube: Cube) -> f32 {
+    2.0 / cube.width.max(cube.height).max(cube.depth) as f32
+}
+
+/// Half the box's size along x, y and z.
+pub fn half_extents(cube: Cube) -> [f32; 3] {
+    let cell_size = cell_size(cube);
+    [
+        cube.width as f32 * cell_size / 2.0,
+        cube.height as f32 * cell_size / 2.0,
+        cube.depth as f32 * cell_size / 2.0,
+    ]
+}
+
+/// World-space unit vectors of a face: (u axis, v axis, outward normal).
+pub fn face_axes(face: Face) -> ([f32; 3], [f32; 3], [f32; 3]) {
+    match face {
+        Face::Front => ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+        Face::Back => ([-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0]),
+        Face::Right => ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        Face::Left => ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0]),
+        Face::Top => ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+        Face::Bottom => ([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    }
+}
+
+/// World-space unit vector of moving `dir` on `face`.
+pub fn direction_axis(face: Face, dir: Direction) -> [f32; 3] {
+    let (u_axis, v_axis, _) = face_axes(face);
+    match dir {
+        Direction::Up => v_axis,
+        Direction::Down => scale(v_axis, -1.0),
+        Direction::Left => scale(u_axis, -1.0),
+        Direction::Right => u_axis,
+    }
+}
+
+/// The direction on `face` that looks most like `screen` to a camera with
+/// the given world-space right and up vectors, so "up" is always up on screen.
+pub fn screen_to_face_direction(
+    face: Face,
+    camera_right: [f32; 3],
+    camera_up: [f32; 3],
+    screen: Direction,
+) -> Direction {
+    let wanted = match screen {
+        Direction::Up => camera_up,
+        Direction::Down => scale(camera_up, -1.0),
+        Direction::Left => scale(camera_right, -1.0),
+        Direction::Right => camera_right,
+    };
+    Direction::ALL
+        .into_iter()
+        .max_by(|&a, &b| {
+            let a = dot(wanted, direction_axis(face, a));
+            let b = dot(wanted, direction_axis(face, b));
+            a.total_cmp(&b)
+        })
+        .unwrap()
+}
+
+/// Center of a cell in world space, `lift` above the face.
+pub fn cell_center(cube: Cube, pos: Position, lift: f32) -> [f32; 3] {
+    let cell_size = cell_size(cube);
+    let half = half_extents(cube);
+    let (u_axis, v_axis, normal) = face_axes(pos.face);
+
+    // Axes are unit vectors along x, y or z, so these pick out the matching half extent
+    let half_u = dot(abs(u_axis), half);
+    let half_v = dot(abs(v_axis), half);
+    let half_n = dot(abs(normal), half);
+
+    let u_local = -half_u + (pos.u as f32 + 0.5) * cell_size;
+    let v_local = -half_v + (pos.v as f32 + 0.5) * cell_size;
+
+    add(
+        add(scale(u_axis, u_local), scale(v_axis, v_local)),
+        scale(normal, half_n + lift),
+    )
+}
+
+/// Where something `t` (0 to 1) of the way from the cell `from` to the cell
+/// `to` is drawn, `lift` above the surface. Between faces that share an edge
+/// it goes over the edge rather than cutting through the box.
+pub fn slide(cube: Cube, from: Position, to: Position, lift: f32, t: f32) -> [f32; 3] {
+    let start = cell_center(cube, from, lift);
+    let end = cell_center(cube, to, lift);
+    let (_, _, to_normal) = face_axes(to.face);
+    if dot(face_axes(from.face).2, to_normal) != 0.0 {
+        // Same face, or opposite faces, which no move goes straight between
+        return lerp(start, end, t);
+    }
+    // Above the shared edge: level with `end` along its normal, and with
+    // `start` along everything else
+    let elbow = add(start, scale(to_normal, dot(end, to_normal) - dot(start, to_normal)));
+    let first = distance(start, elbow);
+    let split = first / (first + distance(elbow, end));
+    if t < split {
+        lerp(start, elbow, t / split)
+    } else {
+        lerp(elbow, end, (t - split) / (1.0 - split))
+    }
+}
+
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    add(a, scale(add(b, scale(a, -1.0)), t))
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let delta = add(b, scale(a, -1.0));
+    dot(delta, delta).sqrt()
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn abs(a: [f32; 3]) -> [f32; 3] {
+    [a[0].abs(), a[1].abs(), a[2].abs()]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+
+    #[test]
+    fn test_logical_neighbors_are_one_cell_apart() {
+        let boards = [
+            Cube::new(1),
+            Cube::new(4),
+            Cube::new(5),
+            Cube::new(10),
+            Cube::cuboid(12, 8, 4),
+            Cube::cuboid(3, 5, 2),
+        ];
+        for cube in boards {
+            let cell = cell_size(cube);
+            for pos in cube.cells() {
+                let from = cell_center(cube, pos, 0.0);
+                for dir in Direction::ALL {
+                    let (next, _) = cube.step(pos, dir).unwrap();
+                    let to = cell_center(cube, next, 0.0);
+                    let delta = add(to, scale(from, -1.0));
+
+                    // Distance over the surface: one cell along a face, or half a
+                    // cell to the edge and half a cell down the other side.
+                    let surface_dist = delta[0].abs() + delta[1].abs() + delta[2].abs();
+                    assert!(
+                        (surface_dist - cell).abs() < 1e-4,
+                        "{cube:?}: {pos:?} {dir:?} -> {next:?} is {surface_dist} apart, cell is {cell}"
+                    );
+
+                    // And the move goes the way the heading points on screen
+                    let forward = dot(delta, direction_axis(pos.face, dir));
+                    assert!(forward > 0.0, "{cube:?}: {pos:?} {dir:?} -> {next:?} moves backwards");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sliding_between_faces_goes_over_the_edge() {
+        for cube in [Cube::new(4), Cube::cuboid(3, 5, 2)] {
+            let half = half_extents(cube);
+            let lift = 0.05;
+            for pos in cube.cells() {
+                for dir in Direction::ALL {
+                    let (next, _) = cube.step(pos, dir).unwrap();
+                    assert_eq!(slide(cube, pos, next, lift, 0.0), cell_center(cube, pos, lift));
+                    for step in 1..=10 {
+                        let t = step as f32 / 10.0;
+                        let p = slide(cube, pos, next, lift, t);
+                        // Never inside the lifted box, so never through the board
+                        let outside = (0..3).any(|axis| p[axis].abs() >= half[axis] + lift - 1e-4);
+                        assert!(outside, "{cube:?}: {pos:?} -> {next:?} at {t} is inside at {p:?}");
+                    }
+                    let end = slide(cube, pos, next, lift, 1.0);
+                    assert!(distance(end, cell_center(cube, next, lift)) < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_screen_directions_follow_camera() {
+        // Camera looking straight at each face, with the renderer's up vectors
+        let views = [
+            (Face::Front, [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            (Face::Back, [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            (Face::Right, [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            (Face::Left, [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            (Face::Top, [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            (Face::Bottom, [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ];
+        for (face, right, up) in views {
+            for dir in Direction::ALL {
+                assert_eq!(screen_to_face_direction(face, right, up, dir), dir, "{face:?} {dir:?}");
+            }
+        }
+
+        // Camera rolled upside down over Top: screen up is face-local down
+        let right = [-1.0, 0.0, 0.0];
+        let up = [0.0, 0.0, 1.0];
+        assert_eq!(screen_to_face_direction(Face::Top, right, up, Direction::Up), Direction::Down);
+        assert_eq!(screen_to_face_direction(Face::Top, right, up, Direction::Right), Direction::Left);
+
+        // Camera rolled a quarter turn over Front
+        let right = [0.0, -1.0, 0.0];
+        let up = [1.0, 0.0, 0.0];
+        assert_eq!(screen_to_face_direction(Face::Front, right, up, Direction::Up), Direction::Right);
+        assert_eq!(screen_to_face_direction(Face::Front, right, up, Direction::Left), Direction::Up);
+    }
+}
@@ -1,48 +1,118 @@
 use serde::{Serialize, Deserialize};
 
+use crate::difficulty::Difficulty;
+use crate::replay::{verify_score, Replay};
+use crate::GRID_SIZE;
+
+/// Each difficulty keeps its own table, all for the one board size the game
+/// is played at (`GRID_SIZE`). Normal uses the original key so
+/// scores from before difficulties existed stay where they were.
+fn storage_key(difficulty: Difficulty) -> String {
+    match difficulty {
+        Difficulty::Normal => "snake3d_scores".to_string(),
+        other => format!("snake3d_scores_{}", other.key()),
+    }
+}
+
+/// Whether an entry's replay was checked to reproduce its score.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryStatus {
+    Verified,
+    /// Saved before replays existed, or its replay doesn't add up. Kept, but
+    /// marked as such.
+    Unverified,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LeaderboardEntry {
     name: String,
     score: u32,
+    /// The game behind the score; entries without one can't be verified.
+    #[serde(default)]
+    replay: Option<Replay>,
+    /// Worked out from the replay each time the board is read, never
+    /// stored, since storage is as easy to edit as the score itself.
+    #[serde(skip)]
+    status: Option<EntryStatus>,
+}
+
+impl LeaderboardEntry {
+    /// Re-simulates the replay to check it reproduces the score, played on
+    /// the game's board at `difficulty`.
+    fn check(&self, difficulty: Difficulty) -> EntryStatus {
+        match &self.replay {
+            Some(replay)
+                if replay.config.width == GRID_SIZE
+                    && replay.config.difficulty.key() == difficulty.key()
+                    && verify_score(replay, self.score).is_ok() =>
+            {
+                EntryStatus::Verified
+            }
+            _ => EntryStatus::Unverified,
+        }
+    }
 }
 
-fn get_leaderboard() -> Result<Vec<LeaderboardEntry>, Box<dyn std::error::Error>> {
+/// Entries in storage for `difficulty`, each checked against its replay.
+fn get_leaderboard(difficulty: Difficulty) -> Result<Vec<LeaderboardEntry>, Box<dyn std::error::Error>> {
     let window = web_sys::window().unwrap();
     let storage = window.local_storage().unwrap().unwrap();
-    if let Ok(Some(json)) = storage.get_item("snake3d_scores") {
-        let entries: Vec<LeaderboardEntry> = serde_json::from_str(&json)?;
+    if let Ok(Some(json)) = storage.get_item(&storage_key(difficulty)) {
+        let mut entries: Vec<LeaderboardEntry> = serde_json::from_str(&json)?;
+        for entry in &mut entries {
+            entry.status = Some(entry.check(difficulty));
+        }
         Ok(entries)
     } else {
         Ok(Vec::new())
     }
 }
 
-pub fn save_score(name: &str, score: u32) {
+pub fn save_score(name: &str, score: u32, replay: &Replay) {
     let window = web_sys::window().unwrap();
     let storage = window.local_storage().unwrap().unwrap();
 
-    let mut entries = get_leaderboard().unwrap_or_default();
-    entries.push(LeaderboardEntry { name: name.to_string(), score });
+    if let Err(err) = verify_score(replay, score) {
+        log::warn!("Rejected leaderboard submission: {err}");
+        return;
+    }
+    if replay.config.width != GRID_SIZE {
+        log::warn!("Rejected leaderboard submission: played on a {} board", replay.config.width);
+        return;
+    }
+
+    // Filed under the difficulty the game was actually played at
+    let difficulty = replay.config.difficulty;
+    let mut entries = get_leaderboard(difficulty).unwrap_or_default();
+    entries.push(LeaderboardEntry {
+        name: name.to_string(),
+        score,
+        replay: Some(replay.clone()),
+        status: Some(EntryStatus::Verified),
+    });
     // Sort by score descending
-    entries.sort_by(|a, b| b.score.cmp(&a.score));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.score));
     // Keep top 10
     if entries.len() > 10 {
         entries.truncate(10);
     }
 
     if let Ok(json) = serde_json::to_string(&entries) {
-        let _ = storage.set_item("snake3d_scores", &json);
+        let _ = storage.set_item(&storage_key(difficulty), &json);
     }
 
-    update_leaderboard_ui();
+    update_leaderboard_ui(difficulty);
 }
 
-pub fn update_leaderboard_ui() {
+pub fn update_leaderboard_ui(difficulty: Difficulty) {
     let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(title) = document.get_element_by_id("leaderboard-title") {
+        title.set_text_content(Some(&format!("Top 10 Players · {}", difficulty.label())));
+    }
     if let Some(list) = document.get_element_by_id("leaderboard-list") {
         list.set_inner_html("");
 
-        match get_leaderboard() {
+        match get_leaderboard(difficulty) {
             Ok(entries) => {
                  if entries.is_empty() {
                      let li = document.create_element("li").unwrap();
@@ -54,7 +124,11 @@ pub fn update_leaderboard_ui() {
                         let li = document.create_element("li").unwrap();
 
                         let name_span = document.create_element("span").unwrap();
-                        name_span.set_text_content(Some(&format!("{}. {}", i + 1, entry.name)));
+                        let mut label = format!("{}. {}", i + 1, entry.name);
+                        if entry.status == Some(EntryStatus::Unverified) {
+                            label += " (unverified)";
+                        }
+                        name_span.set_text_content(Some(&label));
 
                         let score_span = document.create_element("span").unwrap();
                         score_span.set_text_content(Some(&entry.score.to_string()));
@@ -1,17 +1,129 @@
-use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
-use three_d::*;
-use crate::game::{GameState, Direction};
-use crate::renderer::GameRenderer;
-use crate::audio::AudioPlayer;
-
-mod game;
+#[cfg(feature = "web")]
+use {
+    crate::audio::AudioPlayer,
+    crate::campaign::{Campaign, CampaignProgress, Level},
+    crate::difficulty::Difficulty,
+    crate::food::FoodKind,
+    crate::game::{Direction, GameConfig, GameEvent, GameState, Turn},
+    crate::leaderboard::{save_score, update_leaderboard_ui},
+    crate::pause::PauseState,
+    crate::renderer::GameRenderer,
+    crate::replay::ReplayPlayer,
+    crate::scheduler::TickScheduler,
+    crate::settings::ControlScheme,
+    crate::topology::Cube,
+    std::cell::{Cell, RefCell},
+    std::collections::HashMap,
+    std::rc::Rc,
+    three_d::*,
+    wasm_bindgen::prelude::*,
+    wasm_bindgen::JsCast,
+};
+
+pub mod campaign;
+pub mod difficulty;
+pub mod food;
+pub mod game;
+pub mod geometry;
+pub mod obstacles;
+pub mod occupancy;
+pub mod pause;
+pub mod powerups;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod scheduler;
+pub mod topology;
+#[cfg(feature = "web")]
 mod renderer;
+#[cfg(feature = "web")]
 mod audio;
+#[cfg(feature = "web")]
 mod leaderboard;
+#[cfg(feature = "web")]
+mod settings;
+#[cfg(feature = "web")]
+mod autosave;
+
+/// Replay playback controls, from keys or the replay bar.
+#[cfg(feature = "web")]
+#[derive(Clone, Copy)]
+enum ReplayCommand {
+    /// Watch the game that just ended.
+    Start,
+    TogglePause,
+    /// Jump by this many ticks.
+    Seek(i64),
+    Faster,
+    Slower,
+    Exit,
+}
+
+/// Cells along each side of the cube endless games are played on.
+#[cfg(feature = "web")]
+const GRID_SIZE: i32 = 10;
+
+/// Ticks skipped by one press of a seek control.
+#[cfg(feature = "web")]
+const REPLAY_SEEK_TICKS: i64 = 20;
+
+/// What a new game starts: an endless game or a campaign level.
+#[cfg(feature = "web")]
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Endless,
+    Level(usize),
+}
+
+/// What carries over from one game to the next.
+#[cfg(feature = "web")]
+struct Session {
+    grid_size: i32,
+    mode: Mode,
+    campaign: Campaign,
+    /// Best score this session for each level, and for endless games at each difficulty.
+    best_scores: HashMap<String, u32>,
+}
+
+#[cfg(feature = "web")]
+impl Session {
+    fn level(&self) -> Option<(usize, &Level)> {
+        match self.mode {
+            Mode::Endless => None,
+            Mode::Level(index) => Some((index, self.campaign.levels.get(index)?)),
+        }
+    }
 
-use leaderboard::{save_score, update_leaderboard_ui};
+    fn score_key(&self, game: &GameState) -> String {
+        match self.mode {
+            Mode::Endless => game.config.difficulty.key().to_string(),
+            Mode::Level(index) => format!("level-{index}"),
+        }
+    }
+
+    /// Picks up the mode of a game continued from a save.
+    fn resume(&mut self, game: &GameState) {
+        let level = self.campaign.levels.iter().position(|level| level.config == game.config);
+        self.mode = level.map_or(Mode::Endless, Mode::Level);
+    }
+
+    /// Replaces `game` with a fresh one in `mode`, carrying over that mode's
+    /// best score. Endless games are played at `difficulty`; levels bring their own.
+    fn start(&mut self, game: &mut GameState, mode: Mode, difficulty: Difficulty) {
+        let best = self.best_scores.entry(self.score_key(game)).or_default();
+        *best = (*best).max(game.high_score);
+
+        self.mode = mode;
+        let config = match self.level() {
+            Some((_, level)) => level.config.clone(),
+            None => GameConfig { difficulty, ..GameConfig::cube(self.grid_size) },
+        };
+        *game = GameState::with_config(config, rng::entropy_seed());
+        game.high_score = self.best_scores.get(&self.score_key(game)).copied().unwrap_or(0);
+    }
+}
 
+#[cfg(feature = "web")]
 #[wasm_bindgen(start)]
 pub fn init() -> Result<(), JsValue> {
     web_sys::console::log_1(&"Rust: init started".into());
@@ -33,13 +145,12 @@ pub fn init() -> Result<(), JsValue> {
     log::info!("Window created successfully!");
 
     let context = window.gl();
-    let grid_size = 10;
-    let mut game = GameState::new(grid_size);
-    let mut renderer = GameRenderer::new(context, grid_size);
+    let grid_size = GRID_SIZE;
+    let mut renderer = GameRenderer::new(context, Cube::new(grid_size));
     let audio = AudioPlayer::new();
 
     // Game loop variables
-    let mut time_since_last_move = 0.0;
+    let mut scheduler = TickScheduler::default();
     let mut has_logged = false;
 
     // Shared state for mobile controls (Arc<Mutex<>> not needed as closure captures it, but need Interior Mutability for event listeners)
@@ -50,8 +161,85 @@ pub fn init() -> Result<(), JsValue> {
     // But our buttons are HTML elements. `three-d` might not capture clicks on them if they are outside canvas?
     // Actually, we can just check a shared state that the click handlers update.
 
-    use std::rc::Rc;
-    use std::cell::RefCell;
+    // Difficulty for new games; each keeps its own high score
+    let difficulty = Rc::new(Cell::new(settings::load_difficulty()));
+    let difficulty_changed = Rc::new(Cell::new(false));
+    settings::update_difficulty_ui(difficulty.get());
+    if let Some(difficulty_btn) = document.get_element_by_id("difficulty-btn") {
+        let difficulty = difficulty.clone();
+        let changed = difficulty_changed.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            let next = difficulty.get().next();
+            difficulty.set(next);
+            settings::save_difficulty(next);
+            settings::update_difficulty_ui(next);
+            changed.set(true);
+        }) as Box<dyn FnMut()>);
+        difficulty_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // The game is shared so it can be saved from page events while the
+    // render loop isn't running (hidden tabs get no frames)
+    let config = GameConfig { difficulty: difficulty.get(), ..GameConfig::cube(grid_size) };
+    let shared_game = Rc::new(RefCell::new(GameState::with_config(config, rng::entropy_seed())));
+
+    // Campaign levels, each unlocked by finishing the one before
+    let mut session = Session {
+        grid_size,
+        mode: Mode::Endless,
+        campaign: Campaign::builtin(),
+        best_scores: HashMap::new(),
+    };
+    let progress = Rc::new(Cell::new(settings::load_campaign_progress()));
+    let mode_requested = Rc::new(Cell::new(None));
+    let next_level_requested = Rc::new(Cell::new(false));
+    if let Some(list) = document.get_element_by_id("levels-list") {
+        let levels = session.campaign.levels.iter().enumerate();
+        let entries = std::iter::once(("Endless".to_string(), Mode::Endless))
+            .chain(levels.map(|(i, level)| (format!("{}. {}", i + 1, level.name), Mode::Level(i))));
+        for (label, mode) in entries {
+            let li = document.create_element("li").unwrap();
+            li.set_text_content(Some(&label));
+            if let Mode::Level(i) = mode {
+                li.set_id(&format!("level-{i}"));
+            }
+            let progress = progress.clone();
+            let requested = mode_requested.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                if let Mode::Level(i) = mode {
+                    if !progress.get().is_unlocked(i) {
+                        return;
+                    }
+                }
+                requested.set(Some(mode));
+                show_modal("levels-modal", false);
+            }) as Box<dyn FnMut()>);
+            li.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+            closure.forget();
+            list.append_child(&li).unwrap();
+        }
+    }
+    let level_count = session.campaign.levels.len();
+    for (id, show) in [("levels-btn", true), ("close-levels-btn", false)] {
+        if let Some(btn) = document.get_element_by_id(id) {
+            let progress = progress.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                update_levels_ui(progress.get(), level_count);
+                show_modal("levels-modal", show);
+            }) as Box<dyn FnMut()>);
+            btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+            closure.forget();
+        }
+    }
+    if let Some(btn) = document.get_element_by_id("next-level-btn") {
+        let requested = next_level_requested.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            requested.set(true);
+        }) as Box<dyn FnMut()>);
+        btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
 
     let mobile_input = Rc::new(RefCell::new(None));
     let mobile_input_clone = mobile_input.clone();
@@ -126,16 +314,32 @@ pub fn init() -> Result<(), JsValue> {
         closure.forget();
     }
 
+    // Control scheme toggle (absolute arrows vs relative steering)
+    let control_scheme = Rc::new(Cell::new(settings::load_control_scheme()));
+    settings::update_control_scheme_ui(control_scheme.get());
+    if let Some(controls_btn) = document.get_element_by_id("controls-btn") {
+        let scheme = control_scheme.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            let toggled = scheme.get().toggled();
+            scheme.set(toggled);
+            settings::save_control_scheme(toggled);
+            settings::update_control_scheme_ui(toggled);
+        }) as Box<dyn FnMut()>);
+        controls_btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
     // Leaderboard logic setup
     let leaderboard_btn = document.get_element_by_id("leaderboard-btn").unwrap();
     let close_leaderboard_btn = document.get_element_by_id("close-leaderboard-btn").unwrap();
     let submit_score_btn = document.get_element_by_id("submit-score-btn").unwrap();
 
+    let leaderboard_difficulty = difficulty.clone();
     let show_leaderboard = Rc::new(Box::new(move || {
         let document = web_sys::window().unwrap().document().unwrap();
         if let Some(modal) = document.get_element_by_id("leaderboard-modal") {
             modal.class_list().remove_1("hidden").unwrap_or(());
-            update_leaderboard_ui();
+            update_leaderboard_ui(leaderboard_difficulty.get());
         }
     }) as Box<dyn Fn()>);
 
@@ -170,8 +374,9 @@ pub fn init() -> Result<(), JsValue> {
     // Submit score handler
     // We need to access current score. We can't access `game` directly in callback.
     // We can store the pending score in a shared cell when Game Over happens.
-    let pending_score = Rc::new(RefCell::new(0u32));
-    let pending_score_clone = pending_score.clone();
+    // The replay goes along so the leaderboard can verify the score.
+    let pending_submission = Rc::new(RefCell::new(None));
+    let pending_submission_clone = pending_submission.clone();
     let show_leaderboard_clone = show_leaderboard.clone();
     let restart_requested_clone = restart_requested.clone();
 
@@ -182,7 +387,9 @@ pub fn init() -> Result<(), JsValue> {
                 .dyn_into::<web_sys::HtmlInputElement>().unwrap();
             let name = input.value();
             if !name.is_empty() {
-                save_score(&name, *pending_score_clone.borrow());
+                if let Some((score, replay)) = pending_submission_clone.borrow_mut().take() {
+                    save_score(&name, score, &replay);
+                }
                 input.set_value(""); // Clear input
                 show_leaderboard_clone();
                 // Also hide game over screen logic via restart or explicit hide?
@@ -202,6 +409,99 @@ pub fn init() -> Result<(), JsValue> {
         closure.forget();
     }
 
+    // Replay controls
+    let replay_commands = Rc::new(RefCell::new(Vec::new()));
+    for (id, command) in [
+        ("replay-btn", ReplayCommand::Start),
+        ("replay-back", ReplayCommand::Seek(-REPLAY_SEEK_TICKS)),
+        ("replay-toggle", ReplayCommand::TogglePause),
+        ("replay-forward", ReplayCommand::Seek(REPLAY_SEEK_TICKS)),
+        ("replay-slower", ReplayCommand::Slower),
+        ("replay-faster", ReplayCommand::Faster),
+        ("replay-exit", ReplayCommand::Exit),
+    ] {
+        if let Some(btn) = document.get_element_by_id(id) {
+            let commands = replay_commands.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                commands.borrow_mut().push(command);
+            }) as Box<dyn FnMut()>);
+            btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+            closure.forget();
+        }
+    }
+    let mut replay_player: Option<ReplayPlayer> = None;
+    let mut mode_resolved = false;
+
+    // Pause state, shared with the page listeners that pause automatically
+    let pause = Rc::new(Cell::new(PauseState::Running));
+    let pause_toggle_requested = Rc::new(Cell::new(false));
+    for id in ["pause-btn", "resume-btn"] {
+        if let Some(btn) = document.get_element_by_id(id) {
+            let requested = pause_toggle_requested.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                requested.set(true);
+            }) as Box<dyn FnMut()>);
+            btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+            closure.forget();
+        }
+    }
+
+    // Offer to continue a game saved on a previous visit
+    let awaiting_resume = Rc::new(Cell::new(false));
+    if let Some(saved) = autosave::load_saved_game() {
+        let saved = Rc::new(RefCell::new(Some(saved)));
+        awaiting_resume.set(true);
+        autosave::show_continue_prompt(true);
+        for (id, resume) in [("continue-btn", true), ("new-game-btn", false)] {
+            if let Some(btn) = document.get_element_by_id(id) {
+                let saved = saved.clone();
+                let game = shared_game.clone();
+                let awaiting = awaiting_resume.clone();
+                let pause = pause.clone();
+                let closure = Closure::wrap(Box::new(move || {
+                    if !awaiting.get() {
+                        return;
+                    }
+                    match saved.borrow_mut().take() {
+                        Some(saved) if resume => {
+                            *game.borrow_mut() = saved;
+                            // Give the player a moment to find the snake again
+                            let mut state = PauseState::Paused;
+                            state.resume();
+                            pause.set(state);
+                        }
+                        _ => autosave::clear_saved_game(),
+                    }
+                    awaiting.set(false);
+                    autosave::show_continue_prompt(false);
+                }) as Box<dyn FnMut()>);
+                btn.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+                closure.forget();
+            }
+        }
+    }
+
+    // Pause (and save) when the page loses focus or is hidden, which also
+    // covers the tab being closed
+    let attach_auto_pause = |target: &web_sys::EventTarget, event: &str| {
+        let game = shared_game.clone();
+        let awaiting = awaiting_resume.clone();
+        let pause = pause.clone();
+        let closure = Closure::wrap(Box::new(move || {
+            // Until the player answers the prompt, the save on disk is the real game
+            if awaiting.get() {
+                return;
+            }
+            if let Ok(game) = game.try_borrow() {
+                pause_game(&pause, &game);
+            }
+        }) as Box<dyn FnMut()>);
+        target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    };
+    attach_auto_pause(web_sys::window().unwrap().as_ref(), "blur");
+    attach_auto_pause(document.as_ref(), "visibilitychange");
+
     // Hide loading screen
     if let Some(loading_el) = document.get_element_by_id("loading") {
         loading_el.set_attribute("style", "display: none").unwrap();
@@ -216,17 +516,50 @@ pub fn init() -> Result<(), JsValue> {
             has_logged = true;
         }
         let mut events = frame_input.events.clone(); // Clone events to pass to camera and handle locally
-        
+        let mut game = shared_game.borrow_mut();
+
+        // Nothing moves until the player decides whether to continue
+        if awaiting_resume.get() {
+            renderer.update_camera(&mut events);
+            renderer.resize(frame_input.viewport.width, frame_input.viewport.height);
+            renderer.render(&game, &frame_input.screen(), frame_input.elapsed_time / 1000.0, 0.0);
+            return FrameOutput::default();
+        }
+        // A continued game might be a campaign level
+        if !mode_resolved {
+            session.resume(&game);
+            mode_resolved = true;
+        }
+
         // Check for restart request from UI
         if *restart_requested.borrow() {
             if game.game_over {
-                let high_score = game.high_score;
-                game = GameState::new(grid_size);
-                game.high_score = high_score;
+                session.start(&mut game, session.mode, difficulty.get());
             }
             *restart_requested.borrow_mut() = false;
         }
 
+        // A new difficulty applies straight away if there's nothing to lose,
+        // otherwise from the next game. Levels keep their own.
+        if difficulty_changed.replace(false) && session.mode == Mode::Endless && game.score == 0 && !game.game_over {
+            session.start(&mut game, Mode::Endless, difficulty.get());
+        }
+
+        // Picking a level, or moving on from a finished one
+        let mut next_mode = mode_requested.take();
+        if next_level_requested.replace(false) {
+            if let Some((index, _)) = session.level() {
+                if session.campaign.is_unlocked(index + 1, progress.get()) {
+                    next_mode = Some(Mode::Level(index + 1));
+                }
+            }
+        }
+        if let Some(mode) = next_mode {
+            session.start(&mut game, mode, difficulty.get());
+            replay_player = None;
+            pause.set(PauseState::Running);
+        }
+
         // Handle Input
         // Check mobile input
         let mut mobile_dir = None;
@@ -238,47 +571,101 @@ pub fn init() -> Result<(), JsValue> {
             }
         }
 
-        if let Some(dir) = mobile_dir {
-             match dir {
-                Direction::Up => if game.snake.direction != Direction::Down { game.snake.next_direction = Direction::Up; },
-                Direction::Down => if game.snake.direction != Direction::Up { game.snake.next_direction = Direction::Down; },
-                Direction::Left => if game.snake.direction != Direction::Right { game.snake.next_direction = Direction::Left; },
-                Direction::Right => if game.snake.direction != Direction::Left { game.snake.next_direction = Direction::Right; },
-             }
+        // Turns made while paused would be invisible, so they're dropped
+        if let Some(dir) = mobile_dir.filter(|_| pause.get().is_running()) {
+            steer(&mut game, &renderer, control_scheme.get(), dir);
         }
 
+        // Replay keys take over while watching a replay
+        if replay_player.is_some() {
+            for event in &events {
+                if let Event::KeyPress { kind, .. } = event {
+                    let command = match kind {
+                        Key::Space => Some(ReplayCommand::TogglePause),
+                        Key::ArrowLeft => Some(ReplayCommand::Seek(-REPLAY_SEEK_TICKS)),
+                        Key::ArrowRight => Some(ReplayCommand::Seek(REPLAY_SEEK_TICKS)),
+                        Key::ArrowUp => Some(ReplayCommand::Faster),
+                        Key::ArrowDown => Some(ReplayCommand::Slower),
+                        Key::Escape => Some(ReplayCommand::Exit),
+                        _ => None,
+                    };
+                    if let Some(command) = command {
+                        replay_commands.borrow_mut().push(command);
+                    }
+                }
+            }
+        }
+
+        for command in replay_commands.borrow_mut().drain(..) {
+            match (command, replay_player.as_mut()) {
+                (ReplayCommand::Start, None) if game.game_over => {
+                    replay_player = Some(ReplayPlayer::new(game.replay()));
+                    scheduler.reset();
+                }
+                (ReplayCommand::TogglePause, Some(player)) => player.paused = !player.paused,
+                (ReplayCommand::Seek(ticks), Some(player)) => {
+                    let target = player.tick().saturating_add_signed(ticks);
+                    player.seek(target);
+                }
+                (ReplayCommand::Faster, Some(player)) => player.faster(),
+                (ReplayCommand::Slower, Some(player)) => player.slower(),
+                (ReplayCommand::Exit, Some(_)) => replay_player = None,
+                _ => {}
+            }
+        }
+
+        if let Some(player) = replay_player.as_mut() {
+            renderer.update_camera(&mut events);
+            renderer.resize(frame_input.viewport.width, frame_input.viewport.height);
+
+            let dt = if player.paused { 0.0 } else { frame_input.elapsed_time / 1000.0 * player.speed };
+            scheduler.begin_frame(dt);
+            while scheduler.next_tick(player.game().move_interval()) {
+                for event in player.step().unwrap_or_default() {
+                    play_event_effects(&audio, &mut renderer, &event);
+                }
+            }
+
+            update_ui(player.game(), true, session.level(), false);
+            update_replay_ui(Some(player));
+            let alpha = scheduler.alpha(player.game().move_interval());
+            renderer.render(player.game(), &frame_input.screen(), frame_input.elapsed_time / 1000.0, alpha);
+            return FrameOutput::default();
+        }
+        update_replay_ui(None);
+
+        if pause_toggle_requested.replace(false) {
+            toggle_pause(&pause, &game);
+        }
+
+        let running = pause.get().is_running();
         for event in &events {
             if let Event::KeyPress { kind, .. } = event {
                 // Resume audio context on first interaction
                 audio.resume_context();
 
                 match kind {
-                    Key::ArrowUp | Key::W => {
-                        if game.snake.direction != Direction::Down {
-                            game.snake.next_direction = Direction::Up;
-                        }
+                    Key::ArrowUp | Key::W if running => {
+                        steer(&mut game, &renderer, control_scheme.get(), Direction::Up);
                     }
-                    Key::ArrowDown | Key::S => {
-                        if game.snake.direction != Direction::Up {
-                            game.snake.next_direction = Direction::Down;
-                        }
+                    Key::ArrowDown | Key::S if running => {
+                        steer(&mut game, &renderer, control_scheme.get(), Direction::Down);
                     }
-                    Key::ArrowLeft | Key::A => {
-                        if game.snake.direction != Direction::Right {
-                            game.snake.next_direction = Direction::Left;
-                        }
+                    Key::ArrowLeft | Key::A if running => {
+                        steer(&mut game, &renderer, control_scheme.get(), Direction::Left);
                     }
-                    Key::ArrowRight | Key::D => {
-                        if game.snake.direction != Direction::Left {
-                            game.snake.next_direction = Direction::Right;
-                        }
+                    Key::ArrowRight | Key::D if running => {
+                        steer(&mut game, &renderer, control_scheme.get(), Direction::Right);
                     }
-                    Key::R => {
-                        if game.game_over {
-                            let high_score = game.high_score;
-                            game = GameState::new(grid_size);
-                            game.high_score = high_score;
-                        }
+                    Key::C => {
+                        let toggled = control_scheme.get().toggled();
+                        control_scheme.set(toggled);
+                        settings::save_control_scheme(toggled);
+                        settings::update_control_scheme_ui(toggled);
+                    }
+                    Key::P | Key::Escape => toggle_pause(&pause, &game),
+                    Key::R if game.game_over => {
+                        session.start(&mut game, session.mode, difficulty.get());
                     }
                     _ => {}
                 }
@@ -290,42 +677,42 @@ pub fn init() -> Result<(), JsValue> {
         renderer.resize(frame_input.viewport.width, frame_input.viewport.height);
 
         // Update Game Logic
-        // Use accumulated time for fixed step update
-        time_since_last_move += frame_input.elapsed_time / 1000.0; // elapsed_time is ms
-
-        // Calculate current speed based on score (max speed at 50 points)
-        let base_speed = 0.15;
-        let min_speed = 0.05;
-        let speed_reduction = (game.score as f64 * 0.002).min(base_speed - min_speed);
-        let move_interval = base_speed - speed_reduction;
-
-        if time_since_last_move >= move_interval {
-            let old_food_pos = game.food;
-            let event = game.update();
-            match event {
-                crate::game::GameEvent::Eat => {
-                    audio.play_eat();
-                    renderer.spawn_particles(old_food_pos, false);
-                },
-                crate::game::GameEvent::EatPrize => {
-                    audio.play_prize();
-                    renderer.spawn_particles(old_food_pos, true);
-                },
-                crate::game::GameEvent::GameOver => {
-                    audio.play_game_over();
-                    // Update pending score for submit
-                    *pending_score.borrow_mut() = game.score;
-                },
-                crate::game::GameEvent::None => {}
+        // The clock stops while paused and through the resume countdown
+        let mut pause_state = pause.get();
+        pause_state.advance(frame_input.elapsed_time / 1000.0);
+        pause.set(pause_state);
+        let dt = if pause_state.is_running() { frame_input.elapsed_time / 1000.0 } else { 0.0 }; // elapsed_time is ms
+        scheduler.begin_frame(dt);
+
+        while scheduler.next_tick(game.move_interval()) {
+            for event in game.update() {
+                play_event_effects(&audio, &mut renderer, &event);
+                if let GameEvent::TargetReached(_) = event {
+                    if let Some((index, _)) = session.level() {
+                        let mut unlocked = progress.get();
+                        unlocked.complete(index);
+                        progress.set(unlocked);
+                        settings::save_campaign_progress(unlocked);
+                    }
+                }
+                if let GameEvent::Died { .. } | GameEvent::Victory(_) | GameEvent::TargetReached(_) = event {
+                    // Update pending score for submit; level scores don't go on the leaderboard
+                    if session.mode == Mode::Endless {
+                        *pending_submission.borrow_mut() = Some((game.score, game.replay()));
+                    }
+                    autosave::clear_saved_game();
+                }
             }
-            time_since_last_move = 0.0;
         }
 
         // Update UI
-        update_ui(&game);
+        let next_unlocked = session.level().is_some_and(|(index, _)| session.campaign.is_unlocked(index + 1, progress.get()));
+        update_ui(&game, false, session.level(), next_unlocked);
+        update_pause_ui(pause_state);
 
-        // Render
-        renderer.render(&game, &frame_input.screen(), frame_input.elapsed_time / 1000.0);
+        // Render, easing the snake between its last two positions
+        let alpha = scheduler.alpha(game.move_interval());
+        renderer.render(&game, &frame_input.screen(), frame_input.elapsed_time / 1000.0, alpha);
 
         FrameOutput::default()
     });
@@ -333,13 +720,175 @@ pub fn init() -> Result<(), JsValue> {
     Ok(())
 }
 
-fn update_ui(game: &GameState) {
+/// Pauses a game in progress, saving it in case the page doesn't come back.
+#[cfg(feature = "web")]
+fn pause_game(pause: &Cell<PauseState>, game: &GameState) {
+    if game.game_over {
+        return;
+    }
+    let mut state = pause.get();
+    state.pause();
+    pause.set(state);
+    autosave::store_game(game);
+}
+
+#[cfg(feature = "web")]
+fn toggle_pause(pause: &Cell<PauseState>, game: &GameState) {
+    if pause.get().is_running() {
+        pause_game(pause, game);
+    } else {
+        let mut state = pause.get();
+        state.toggle();
+        pause.set(state);
+    }
+}
+
+#[cfg(feature = "web")]
+fn show_modal(id: &str, visible: bool) {
     let document = web_sys::window().unwrap().document().unwrap();
-    
+    if let Some(modal) = document.get_element_by_id(id) {
+        modal.class_list().toggle_with_force("hidden", !visible).unwrap_or(false);
+    }
+}
+
+/// Marks each level in the picker as locked, unlocked or finished.
+#[cfg(feature = "web")]
+fn update_levels_ui(progress: CampaignProgress, level_count: usize) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    for i in 0..level_count {
+        if let Some(li) = document.get_element_by_id(&format!("level-{i}")) {
+            let class_list = li.class_list();
+            class_list.toggle_with_force("locked", !progress.is_unlocked(i)).unwrap_or(false);
+            class_list.toggle_with_force("completed", i < progress.completed).unwrap_or(false);
+        }
+    }
+}
+
+/// Sounds and particles for a game event.
+#[cfg(feature = "web")]
+fn play_event_effects(audio: &AudioPlayer, renderer: &mut GameRenderer, event: &GameEvent) {
+    match *event {
+        GameEvent::Ate { kind, pos } => {
+            match kind {
+                FoodKind::Prize => audio.play_prize(),
+                // Its own sound plays when it starts
+                FoodKind::PowerUp(_) => {}
+                _ => audio.play_eat(),
+            }
+            renderer.spawn_particles(pos, kind);
+        }
+        GameEvent::PowerUpStarted { power, .. } => audio.play_power_up(power),
+        GameEvent::PowerUpExpired(_) => audio.play_power_down(),
+        GameEvent::FoodExpiring { .. } => audio.play_warning_tick(),
+        GameEvent::Died { .. } => audio.play_game_over(),
+        GameEvent::Victory(_) => audio.play_victory(),
+        _ => {}
+    }
+}
+
+/// Queues the turn an arrow, button or swipe in screen direction `dir` stands for under `scheme`.
+#[cfg(feature = "web")]
+fn steer(game: &mut GameState, renderer: &GameRenderer, scheme: ControlScheme, dir: Direction) {
+    match scheme {
+        ControlScheme::Absolute => {
+            // Map through the camera so "up" is up on screen whichever face is in view
+            let dir = renderer.screen_to_direction(game.snake.head().face, dir);
+            game.queue_turn(dir);
+        }
+        ControlScheme::Relative => match dir {
+            Direction::Left => {
+                game.queue_relative_turn(Turn::Left);
+            }
+            Direction::Right => {
+                game.queue_relative_turn(Turn::Right);
+            }
+            Direction::Up | Direction::Down => {}
+        },
+    }
+}
+
+/// Shows the replay bar while a replay is playing.
+#[cfg(feature = "web")]
+fn update_replay_ui(player: Option<&ReplayPlayer>) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(bar) = document.get_element_by_id("replay-bar") {
+        bar.class_list().toggle_with_force("hidden", player.is_none()).unwrap_or(false);
+    }
+    if let (Some(player), Some(status_el)) = (player, document.get_element_by_id("replay-status")) {
+        status_el.set_inner_html(&format!(
+            "{} Replay {}x &middot; {} / {}",
+            if player.paused { "⏸" } else { "▶" },
+            player.speed,
+            player.tick(),
+            player.replay().ticks
+        ));
+    }
+}
+
+#[cfg(feature = "web")]
+fn update_pause_ui(state: PauseState) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("pause-overlay") {
+        overlay.class_list().toggle_with_force("hidden", state.is_running()).unwrap_or(false);
+    }
+    if let Some(title_el) = document.get_element_by_id("pause-title") {
+        let title = match state.countdown() {
+            Some(seconds) => seconds.to_string(),
+            None => "Paused".to_string(),
+        };
+        title_el.set_text_content(Some(&title));
+    }
+    if let Some(resume_btn) = document.get_element_by_id("resume-btn") {
+        resume_btn.class_list().toggle_with_force("hidden", state != PauseState::Paused).unwrap_or(false);
+    }
+    if let Some(pause_btn) = document.get_element_by_id("pause-btn") {
+        pause_btn.set_text_content(Some(if state.is_running() { "⏸ Pause" } else { "▶ Resume" }));
+    }
+}
+
+/// `replaying` hides the game-over screen while a finished game is played back.
+/// `level` is the campaign level being played, if any, and `next_unlocked`
+/// whether the one after it is open.
+#[cfg(feature = "web")]
+fn update_ui(game: &GameState, replaying: bool, level: Option<(usize, &Level)>, next_unlocked: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    if let Some(level_el) = document.get_element_by_id("level-info") {
+        match level {
+            Some((index, level)) => {
+                let mut text = format!("Level {}: {}", index + 1, level.name);
+                if let Some(target) = game.config.target_score {
+                    text += &format!(" &middot; Goal {target}");
+                }
+                if let Some(left) = game.time_left() {
+                    text += &format!(" &middot; {}s", left.ceil() as u32);
+                }
+                level_el.set_inner_html(&text);
+                level_el.class_list().remove_1("hidden").unwrap_or(());
+            }
+            None => level_el.class_list().add_1("hidden").unwrap_or(()),
+        }
+    }
+
     if let Some(score_el) = document.get_element_by_id("score") {
         score_el.set_inner_html(&game.score.to_string());
     }
 
+    // Running power-ups, with roughly how long each has left at the current pace
+    if let Some(power_ups_el) = document.get_element_by_id("power-ups") {
+        let text: Vec<String> = game
+            .effects
+            .active()
+            .iter()
+            .map(|effect| {
+                let seconds = game.effects.ticks_left(effect.power, game.ticks) as f64 * game.move_interval();
+                format!("{} {}s", effect.power.label(), seconds.ceil() as u32)
+            })
+            .collect();
+        power_ups_el.set_inner_html(&text.join(" &middot; "));
+        power_ups_el.class_list().toggle_with_force("hidden", text.is_empty()).unwrap_or(false);
+    }
+
     if let Some(high_score_el) = document.get_element_by_id("high-score") {
         high_score_el.set_inner_html(&game.high_score.to_string());
         if let Some(container) = document.get_element_by_id("high-score-container") {
@@ -349,11 +898,44 @@ fn update_ui(game: &GameState) {
 
     if let Some(game_over_el) = document.get_element_by_id("game-over") {
         let class_list = game_over_el.class_list();
-        if game.game_over {
+        if game.game_over && !replaying {
             class_list.remove_1("hidden").unwrap();
             if let Some(final_score_el) = document.get_element_by_id("final-score") {
                 final_score_el.set_inner_html(&format!("Score: {}", game.score));
             }
+            // A filled cube or a finished level gets its own title and the run's stats
+            class_list.toggle_with_force("victory", game.won).unwrap_or(false);
+            if let Some(title_el) = document.get_element_by_id("game-over-title") {
+                let title = if game.won && game.config.target_score.is_some() {
+                    "Level Complete!"
+                } else if game.won {
+                    "Perfect Game!"
+                } else if game.time_left() == Some(0.0) {
+                    "Time's Up!"
+                } else {
+                    "Game Over"
+                };
+                title_el.set_inner_html(title);
+            }
+            // Level scores don't go on the leaderboard
+            if let Some(name_entry) = document.get_element_by_id("name-entry") {
+                name_entry.class_list().toggle_with_force("hidden", level.is_some()).unwrap_or(false);
+            }
+            if let Some(next_btn) = document.get_element_by_id("next-level-btn") {
+                next_btn.class_list().toggle_with_force("hidden", !next_unlocked).unwrap_or(false);
+            }
+            if let Some(stats_el) = document.get_element_by_id("final-stats") {
+                if game.won {
+                    let stats = game.stats();
+                    stats_el.set_inner_html(&format!(
+                        "Length {} &middot; {} eaten &middot; {} moves",
+                        stats.length, stats.food_eaten, stats.ticks
+                    ));
+                    stats_el.class_list().remove_1("hidden").unwrap_or(());
+                } else {
+                    stats_el.class_list().add_1("hidden").unwrap_or(());
+                }
+            }
         } else {
             class_list.add_1("hidden").unwrap();
         }
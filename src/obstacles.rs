@@ -0,0 +1,402 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Direction, Face, Position};
+use crate::occupancy::Occupancy;
+use crate::rng::Rng;
+use crate::topology::Topology;
+
+/// Cells ahead of the start kept clear, so no game opens facing a wall.
+pub const START_CLEARANCE: usize = 3;
+
+/// Free neighbours every free cell keeps in a generated layout, so there's
+/// always a way out again (unless the surface itself gives it fewer).
+pub const MIN_FREE_NEIGHBOURS: usize = 2;
+
+/// Where a game's static walls go.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ObstacleLayout {
+    #[default]
+    None,
+    /// Exactly these cells. Any off the board or on the snake's opening run
+    /// are left out.
+    Cells(Vec<Position>),
+    /// `count` cells scattered using the game's seed.
+    Random { count: u32 },
+    /// Up to `density` (0 to 1) of the board walled off using the game's
+    /// seed, keeping every free cell reachable from the start and free of
+    /// dead ends.
+    Generated {
+        density: f64,
+        #[serde(default)]
+        symmetry: Symmetry,
+    },
+}
+
+/// How generated walls repeat around the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Every wall has a twin on the opposite face, at the cell straight
+    /// through the box's center.
+    Opposite,
+}
+
+impl Symmetry {
+    /// The cells that get walled together with `pos`, `pos` first.
+    fn group(self, topology: &impl Topology, pos: Position) -> Vec<Position> {
+        let mut group = vec![pos];
+        if self == Symmetry::Opposite {
+            if let Some(twin) = opposite_cell(topology, pos) {
+                if twin != pos {
+                    group.push(twin);
+                }
+            }
+        }
+        group
+    }
+}
+
+/// The cell straight through the box's center from `pos`, if the opposite
+/// face has the same shape.
+fn opposite_cell(topology: &impl Topology, pos: Position) -> Option<Position> {
+    let face = match pos.face {
+        Face::Front => Face::Back,
+        Face::Back => Face::Front,
+        Face::Left => Face::Right,
+        Face::Right => Face::Left,
+        Face::Top => Face::Bottom,
+        Face::Bottom => Face::Top,
+    };
+    let (width, height) = topology.extent(pos.face);
+    if topology.extent(face) != (width, height) {
+        return None;
+    }
+    // Side faces share their u direction with the opposite one and flip v;
+    // Top and Bottom share v and flip u (see `geometry::face_axes`)
+    let (u, v) = match pos.face {
+        Face::Top | Face::Bottom => (width - 1 - pos.u, pos.v),
+        _ => (pos.u, height - 1 - pos.v),
+    };
+    Some(Position { face, u, v })
+}
+
+impl ObstacleLayout {
+    /// Resolves the layout to cells on `topology`, leaving `keep_clear` open
+    /// and at least one other cell free for food.
+    pub fn place(&self, topology: &impl Topology, keep_clear: &[Position], rng: &mut Rng) -> Vec<Position> {
+        let mut open: Vec<Position> = topology.cells().into_iter().filter(|p| !keep_clear.contains(p)).collect();
+        match self {
+            ObstacleLayout::None => Vec::new(),
+            ObstacleLayout::Cells(cells) => {
+                let open: HashSet<Position> = open.into_iter().collect();
+                let mut seen = HashSet::new();
+                let mut placed: Vec<Position> =
+                    cells.iter().copied().filter(|p| open.contains(p) && seen.insert(*p)).collect();
+                placed.truncate(open.len().saturating_sub(1));
+                placed
+            }
+            ObstacleLayout::Random { count } => {
+                // Partial Fisher-Yates: the first `count` cells end up a uniform sample
+                let count = (*count as usize).min(open.len().saturating_sub(1));
+                for i in 0..count {
+                    let j = i + rng.below((open.len() - i) as u32) as usize;
+                    open.swap(i, j);
+                }
+                open.truncate(count);
+                open
+            }
+            ObstacleLayout::Generated { density, symmetry } => {
+                let total = topology.cells().len() as f64;
+                let target = (density.clamp(0.0, 1.0) * total).round() as usize;
+                generate(topology, keep_clear, open, target, *symmetry, rng)
+            }
+        }
+    }
+}
+
+/// Walls off up to `target` of the `open` cells, trying them in a seeded
+/// order and keeping only walls that leave the board playable: every free
+/// cell reachable from the start (`keep_clear[0]`) and none with fewer than
+/// `MIN_FREE_NEIGHBOURS` free neighbours.
+fn generate(
+    topology: &impl Topology,
+    keep_clear: &[Position],
+    mut open: Vec<Position>,
+    target: usize,
+    symmetry: Symmetry,
+    rng: &mut Rng,
+) -> Vec<Position> {
+    let Some(&start) = keep_clear.first() else {
+        return Vec::new();
+    };
+    for i in (1..open.len()).rev() {
+        let j = rng.below(i as u32 + 1) as usize;
+        open.swap(i, j);
+    }
+
+    let cells = topology.cells();
+    let mut blocked = Occupancy::new(topology);
+    let mut free = cells.len();
+    let mut placed = Vec::new();
+    for pos in open {
+        let group = symmetry.group(topology, pos);
+        if placed.len() + group.len() > target {
+            continue;
+        }
+        if group.iter().any(|p| blocked.contains(*p) || keep_clear.contains(p)) {
+            continue;
+        }
+        group.iter().for_each(|&p| blocked.insert(p));
+        if is_playable(topology, &blocked, &group, start, free - group.len()) {
+            free -= group.len();
+            placed.extend(group);
+        } else {
+            group.iter().for_each(|&p| blocked.remove(p));
+        }
+    }
+    placed
+}
+
+/// Cells the search round new walls in `is_playable` visits before giving up
+/// and flood-filling the whole board.
+const LOCAL_SEARCH_CELLS: usize = 1024;
+
+/// Whether the layout with `walls` just added still keeps its promises,
+/// given that it kept them before. Only the new walls' neighbours can lose a
+/// free neighbour. Any cell can be cut off, but only if the walls' free
+/// neighbours no longer join up; a search out from them usually settles that
+/// without touching the rest of the board, and failing that, reachability is
+/// checked with a flood fill from `start`.
+fn is_playable(topology: &impl Topology, blocked: &Occupancy, walls: &[Position], start: Position, free: usize) -> bool {
+    let free_neighbours = |pos: Position| -> Vec<Position> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|dir| topology.step(pos, dir))
+            .map(|(next, _)| next)
+            .filter(|&next| !blocked.contains(next))
+            .collect()
+    };
+    let all_neighbours = |pos: Position| Direction::ALL.into_iter().filter(|&dir| topology.step(pos, dir).is_some()).count();
+
+    for &wall in walls {
+        for dir in Direction::ALL {
+            let Some((next, _)) = topology.step(wall, dir) else { continue };
+            if !blocked.contains(next) && free_neighbours(next).len() < MIN_FREE_NEIGHBOURS.min(all_neighbours(next)) {
+                return false;
+            }
+        }
+    }
+
+    // Any path through a wall went in and out through its free neighbours,
+    // so if those still join up, so does everything else. Walls side by side
+    // are gone through together, so their neighbours have to join up as one.
+    let wall_set: HashSet<Position> = walls.iter().copied().collect();
+    let touching = walls
+        .iter()
+        .any(|&wall| Direction::ALL.into_iter().filter_map(|dir| topology.step(wall, dir)).any(|(next, _)| wall_set.contains(&next)));
+    let mut around: Vec<Vec<Position>> = walls.iter().map(|&wall| free_neighbours(wall)).collect();
+    if touching {
+        around = vec![around.concat()];
+    }
+    let mut unsure = false;
+    for cells in around {
+        match joined_nearby(cells, free_neighbours) {
+            Some(true) => {}
+            Some(false) => return false,
+            None => unsure = true,
+        }
+    }
+    if !unsure {
+        return true;
+    }
+
+    let mut seen = Occupancy::new(topology);
+    seen.insert(start);
+    let mut stack = vec![start];
+    let mut reached = 0;
+    while let Some(pos) = stack.pop() {
+        reached += 1;
+        for next in free_neighbours(pos) {
+            if !seen.contains(next) {
+                seen.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+    reached == free
+}
+
+/// Whether `cells` are all joined by free cells, found by searching out
+/// from the first. `None` if that takes more than `LOCAL_SEARCH_CELLS`.
+fn joined_nearby(mut cells: Vec<Position>, free_neighbours: impl Fn(Position) -> Vec<Position>) -> Option<bool> {
+    let Some(&first) = cells.first() else {
+        return Some(true);
+    };
+    let mut seen = HashSet::from([first]);
+    let mut queue = VecDeque::from([first]);
+    while let Some(pos) = queue.pop_front() {
+        cells.retain(|&p| p != pos);
+        if cells.is_empty() {
+            return Some(true);
+        }
+        if seen.len() >= LOCAL_SEARCH_CELLS {
+            return None;
+        }
+        for next in free_neighbours(pos) {
+            if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    Some(false)
+}
+
+/// The start cell and the cells the snake will cross first if it goes straight.
+pub fn opening_run(topology: &impl Topology, start: Position, heading: Direction) -> Vec<Position> {
+    let mut run = vec![start];
+    let (mut pos, mut dir) = (start, heading);
+    for _ in 0..START_CLEARANCE {
+        match topology.step(pos, dir) {
+            Some((next, next_dir)) => {
+                run.push(next);
+                (pos, dir) = (next, next_dir);
+            }
+            None => break,
+        }
+    }
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Face;
+    use crate::topology::Cube;
+
+    #[test]
+    fn test_random_layout_avoids_opening_run_and_repeats_per_seed() {
+        let cube = Cube::new(4);
+        let start = Position { face: Face::Front, u: 2, v: 2 };
+        let keep_clear = opening_run(&cube, start, Direction::Up);
+        assert_eq!(keep_clear.len(), START_CLEARANCE + 1);
+
+        let layout = ObstacleLayout::Random { count: 40 };
+        let placed = layout.place(&cube, &keep_clear, &mut Rng::new(9));
+        assert_eq!(placed.len(), 40);
+        assert!(placed.iter().all(|p| !keep_clear.contains(p)));
+        assert_eq!(placed.iter().collect::<HashSet<_>>().len(), 40, "no cell placed twice");
+        assert_eq!(placed, layout.place(&cube, &keep_clear, &mut Rng::new(9)));
+    }
+
+    #[test]
+    fn test_fixed_cells_are_filtered() {
+        let cube = Cube::new(3);
+        let start = Position { face: Face::Front, u: 1, v: 1 };
+        let wall = Position { face: Face::Left, u: 0, v: 0 };
+        let layout = ObstacleLayout::Cells(vec![
+            wall,
+            wall,
+            start,
+            Position { face: Face::Top, u: 5, v: 0 },
+        ]);
+        assert_eq!(layout.place(&cube, &[start], &mut Rng::new(1)), vec![wall]);
+    }
+
+    /// Checks every free cell afresh rather than trusting the layout before,
+    /// unlike `is_playable`.
+    fn is_whole_board_playable(cube: &Cube, walls: &[Position], start: Position) -> bool {
+        let mut blocked = Occupancy::new(cube);
+        walls.iter().for_each(|&p| blocked.insert(p));
+        let free: Vec<Position> = cube.cells().into_iter().filter(|&p| !blocked.contains(p)).collect();
+        let free_neighbours = |pos: Position| {
+            Direction::ALL.into_iter().map(|dir| cube.step(pos, dir).unwrap().0).filter(|&next| !blocked.contains(next)).collect::<Vec<_>>()
+        };
+        if free.iter().any(|&pos| free_neighbours(pos).len() < MIN_FREE_NEIGHBOURS) {
+            return false;
+        }
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            stack.extend(free_neighbours(pos).into_iter().filter(|&next| seen.insert(next)));
+        }
+        seen.len() == free.len()
+    }
+
+    #[test]
+    fn test_wall_checks_agree_with_checking_the_whole_board() {
+        // Walls tried a pair at a time in a seeded order, as `generate` does,
+        // until the board is as full as it can get
+        let cube = Cube::cuboid(9, 7, 5);
+        let start = Position { face: Face::Front, u: 4, v: 3 };
+        let mut blocked = Occupancy::new(&cube);
+        let mut walls = Vec::new();
+        let mut order = cube.cells();
+        let mut rng = Rng::new(21);
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.below(i as u32 + 1) as usize);
+        }
+        for pos in order {
+            let group = Symmetry::Opposite.group(&cube, pos);
+            if group.iter().any(|&p| p == start || blocked.contains(p)) {
+                continue;
+            }
+            group.iter().for_each(|&p| blocked.insert(p));
+            let free = cube.cells().len() - walls.len() - group.len();
+            let with_group: Vec<Position> = walls.iter().chain(&group).copied().collect();
+            let playable = is_playable(&cube, &blocked, &group, start, free);
+            assert_eq!(playable, is_whole_board_playable(&cube, &with_group, start), "walling {group:?}");
+            if playable {
+                walls = with_group;
+            } else {
+                group.iter().for_each(|&p| blocked.remove(p));
+            }
+        }
+        assert!(walls.len() > 50, "only {} walls", walls.len());
+    }
+
+    #[test]
+    fn test_generated_layout_stays_connected_without_dead_ends() {
+        let cube = Cube::new(6);
+        let start = Position { face: Face::Front, u: 3, v: 3 };
+        let keep_clear = opening_run(&cube, start, Direction::Up);
+        let layout = ObstacleLayout::Generated { density: 0.3, symmetry: Symmetry::None };
+        let placed = layout.place(&cube, &keep_clear, &mut Rng::new(4));
+        assert!(placed.len() > 20, "only {} walls", placed.len());
+        assert!(placed.len() <= 65);
+        assert_eq!(placed, layout.place(&cube, &keep_clear, &mut Rng::new(4)));
+
+        assert!(is_whole_board_playable(&cube, &placed, start));
+    }
+
+    #[test]
+    fn test_generated_layout_on_the_largest_board() {
+        let cube = Cube::new(crate::replay::MAX_VERIFIED_SIDE);
+        let start = Position { face: Face::Front, u: 32, v: 32 };
+        let keep_clear = opening_run(&cube, start, Direction::Up);
+        let layout = ObstacleLayout::Generated { density: 0.3, symmetry: Symmetry::Opposite };
+        let placed = layout.place(&cube, &keep_clear, &mut Rng::new(8));
+        assert!(placed.len() > cube.cells().len() / 5, "only {} walls", placed.len());
+
+        assert!(is_whole_board_playable(&cube, &placed, start));
+    }
+
+    #[test]
+    fn test_opposite_symmetry_walls_in_pairs_through_the_center() {
+        let cube = Cube::cuboid(5, 4, 3);
+        for pos in cube.cells() {
+            let twin = opposite_cell(&cube, pos).unwrap();
+            let [x, y, z] = crate::geometry::cell_center(cube, pos, 0.0);
+            let [tx, ty, tz] = crate::geometry::cell_center(cube, twin, 0.0);
+            assert!((x + tx).abs() + (y + ty).abs() + (z + tz).abs() < 1e-5, "{pos:?} and {twin:?}");
+        }
+
+        let start = Position { face: Face::Front, u: 2, v: 1 };
+        let layout = ObstacleLayout::Generated { density: 0.2, symmetry: Symmetry::Opposite };
+        let placed: HashSet<Position> = layout.place(&cube, &[start], &mut Rng::new(11)).into_iter().collect();
+        assert!(!placed.is_empty());
+        assert!(placed.iter().all(|&p| placed.contains(&opposite_cell(&cube, p).unwrap())));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::game::{Face, Position};
+use crate::topology::Topology;
+
+/// Per-cell counters indexed by (face, u, v), for O(1) "is anything here?" checks.
+///
+/// Counts rather than flags, because a cell can briefly hold two segments
+/// (the head moving onto the tail's cell before the tail leaves it).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Occupancy {
+    max_u: i32,
+    max_v: i32,
+    counts: Vec<u16>,
+}
+
+impl Occupancy {
+    pub fn new(topology: &impl Topology) -> Self {
+        let (max_u, max_v) = Face::ALL
+            .iter()
+            .map(|&face| topology.extent(face))
+            .fold((0, 0), |(mu, mv), (u, v)| (mu.max(u), mv.max(v)));
+        Self {
+            max_u,
+            max_v,
+            counts: vec![0; Face::ALL.len() * (max_u * max_v) as usize],
+        }
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        debug_assert!((0..self.max_u).contains(&pos.u) && (0..self.max_v).contains(&pos.v));
+        (pos.face as usize * self.max_u as usize + pos.u as usize) * self.max_v as usize + pos.v as usize
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.count(pos) > 0
+    }
+
+    pub fn count(&self, pos: Position) -> u16 {
+        self.counts[self.index(pos)]
+    }
+
+    pub fn insert(&mut self, pos: Position) {
+        let i = self.index(pos);
+        self.counts[i] += 1;
+    }
+
+    pub fn remove(&mut self, pos: Position) {
+        let i = self.index(pos);
+        self.counts[i] -= 1;
+    }
+}
@@ -0,0 +1,99 @@
+/// Seconds counted down before a paused game starts moving again.
+pub const RESUME_COUNTDOWN: f64 = 3.0;
+
+/// Whether the game clock is running, stopped, or about to restart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseState {
+    Running,
+    Paused,
+    /// Counting down to `Running`; the game stays frozen meanwhile.
+    Resuming { remaining: f64 },
+}
+
+impl PauseState {
+    pub fn is_running(self) -> bool {
+        self == PauseState::Running
+    }
+
+    /// Stops the game, including during a resume countdown.
+    pub fn pause(&mut self) {
+        *self = PauseState::Paused;
+    }
+
+    /// Starts the countdown back to `Running`. Does nothing unless paused.
+    pub fn resume(&mut self) {
+        if *self == PauseState::Paused {
+            *self = PauseState::Resuming { remaining: RESUME_COUNTDOWN };
+        }
+    }
+
+    /// Pauses a running game or resumes a paused one. Returns whether this paused it.
+    pub fn toggle(&mut self) -> bool {
+        match *self {
+            PauseState::Running => {
+                self.pause();
+                true
+            }
+            PauseState::Paused => {
+                self.resume();
+                false
+            }
+            // Interrupting a countdown goes back to paused
+            PauseState::Resuming { .. } => {
+                self.pause();
+                true
+            }
+        }
+    }
+
+    /// Runs the resume countdown forward by `dt` seconds.
+    pub fn advance(&mut self, dt: f64) {
+        if let PauseState::Resuming { remaining } = *self {
+            *self = if remaining > dt {
+                PauseState::Resuming { remaining: remaining - dt }
+            } else {
+                PauseState::Running
+            };
+        }
+    }
+
+    /// Whole seconds left on the resume countdown, for display.
+    pub fn countdown(self) -> Option<u32> {
+        match self {
+            PauseState::Resuming { remaining } => Some(remaining.ceil() as u32),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_counts_down_before_running() {
+        let mut state = PauseState::Running;
+        assert!(state.toggle());
+        state.advance(10.0);
+        assert_eq!(state, PauseState::Paused, "paused games don't unpause on their own");
+
+        assert!(!state.toggle());
+        assert_eq!(state.countdown(), Some(3));
+        state.advance(1.5);
+        assert_eq!(state.countdown(), Some(2));
+        assert!(!state.is_running());
+        state.advance(1.5);
+        assert!(state.is_running());
+    }
+
+    #[test]
+    fn test_pausing_interrupts_countdown() {
+        let mut state = PauseState::Paused;
+        state.resume();
+        state.advance(1.0);
+        assert!(state.toggle());
+        assert_eq!(state, PauseState::Paused);
+        state.resume();
+        assert_eq!(state.countdown(), Some(3), "countdown starts over");
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// How much longer each move takes under slow-mo.
+pub const SLOW_MO_FACTOR: f64 = 1.5;
+/// Farthest a magnet reaches, in cells along the head's face.
+pub const MAGNET_RANGE: i32 = 4;
+/// Picking up a power-up that's already running adds its duration, up to
+/// this many durations ahead.
+pub const MAX_STACKED_DURATIONS: u64 = 2;
+
+/// A temporary effect picked up from a power-up item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUp {
+    /// The snake passes through its own body.
+    Ghost,
+    /// Food near the head drifts a cell towards it each move.
+    Magnet,
+    /// Moves take longer.
+    SlowMo,
+    /// Food scores twice its points.
+    DoubleScore,
+}
+
+impl PowerUp {
+    /// Moves the effect lasts for.
+    pub fn duration(self) -> u64 {
+        match self {
+            PowerUp::Ghost => 30,
+            PowerUp::Magnet => 60,
+            PowerUp::SlowMo => 40,
+            PowerUp::DoubleScore => 50,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerUp::Ghost => "Ghost",
+            PowerUp::Magnet => "Magnet",
+            PowerUp::SlowMo => "Slow-mo",
+            PowerUp::DoubleScore => "Double Score",
+        }
+    }
+}
+
+/// A running power-up. Counted in ticks rather than seconds so replays
+/// expire it on exactly the same move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveEffect {
+    pub power: PowerUp,
+    /// Last tick the effect applies to.
+    pub until_tick: u64,
+}
+
+/// The power-ups running in a game, at most one entry per kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Effects {
+    active: Vec<ActiveEffect>,
+}
+
+impl Effects {
+    pub fn is_active(&self, power: PowerUp) -> bool {
+        self.active.iter().any(|effect| effect.power == power)
+    }
+
+    pub fn active(&self) -> &[ActiveEffect] {
+        &self.active
+    }
+
+    /// Moves `power` has left as of tick `now`, 0 if it isn't running.
+    pub fn ticks_left(&self, power: PowerUp, now: u64) -> u64 {
+        self.active.iter().find(|effect| effect.power == power).map_or(0, |effect| effect.until_tick.saturating_sub(now))
+    }
+
+    /// Starts `power` after tick `now`, or extends it if it's already
+    /// running. Returns the moves it now has left.
+    pub fn grant(&mut self, power: PowerUp, now: u64) -> u64 {
+        let cap = now + power.duration() * MAX_STACKED_DURATIONS;
+        match self.active.iter_mut().find(|effect| effect.power == power) {
+            Some(effect) => effect.until_tick = (effect.until_tick + power.duration()).min(cap),
+            None => self.active.push(ActiveEffect { power, until_tick: now + power.duration() }),
+        }
+        self.ticks_left(power, now)
+    }
+
+    /// Whether these effects could be running at tick `now`: one entry per
+    /// kind, none ending later than stacking allows.
+    pub fn is_possible_at(&self, now: u64) -> bool {
+        self.active.iter().enumerate().all(|(i, effect)| {
+            let cap = now.saturating_add(effect.power.duration() * MAX_STACKED_DURATIONS);
+            effect.until_tick <= cap && !self.active[..i].iter().any(|other| other.power == effect.power)
+        })
+    }
+
+    /// Drops the effects that ran out before tick `now`, returning them in
+    /// the order they were picked up.
+    pub fn expire(&mut self, now: u64) -> Vec<PowerUp> {
+        let expired = self.active.iter().filter(|effect| effect.until_tick < now).map(|effect| effect.power).collect();
+        self.active.retain(|effect| effect.until_tick >= now);
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_expire_after_their_duration() {
+        let mut effects = Effects::default();
+        assert_eq!(effects.grant(PowerUp::Ghost, 10), 30);
+        effects.grant(PowerUp::SlowMo, 20);
+        assert!(effects.expire(40).is_empty());
+        assert_eq!(effects.expire(41), vec![PowerUp::Ghost]);
+        assert!(!effects.is_active(PowerUp::Ghost));
+        assert_eq!(effects.ticks_left(PowerUp::SlowMo, 41), 19);
+    }
+
+    #[test]
+    fn test_same_power_up_stacks_up_to_a_cap() {
+        let mut effects = Effects::default();
+        effects.grant(PowerUp::DoubleScore, 0);
+        assert_eq!(effects.grant(PowerUp::DoubleScore, 5), 95);
+        assert_eq!(effects.grant(PowerUp::DoubleScore, 5), 100, "capped at two durations ahead");
+        assert_eq!(effects.active().len(), 1);
+    }
+}
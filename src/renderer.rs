@@ -1,5 +1,14 @@
 use three_d::*;
-use crate::game::{GameState, Position, Face};
+use crate::food::{FoodKind, EXPIRY_WARNING_TICKS};
+use crate::game::{Direction, GameState, Position, Face};
+use crate::powerups::PowerUp;
+use crate::rng::Rng;
+use crate::scheduler::MAX_CATCH_UP_TICKS;
+use crate::topology::Cube;
+use crate::geometry;
+
+/// Beads in the full countdown ring round an expiring item.
+const RING_BEADS: usize = 16;
 
 pub struct GameRenderer {
     context: Context,
@@ -8,11 +17,23 @@ pub struct GameRenderer {
     board_instances: Gm<InstancedMesh, PhysicalMaterial>,
     grid_instances: Gm<InstancedMesh, PhysicalMaterial>,
     snake_instances: Gm<InstancedMesh, PhysicalMaterial>,
-    food_mesh: Gm<Mesh, PhysicalMaterial>,
-    prize_mesh: Gm<Mesh, PhysicalMaterial>,
+    obstacle_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    /// Obstacles the instances were last built for; they only change with the game.
+    obstacle_cells: Vec<Position>,
+    /// Spheres for every kind but prizes, tinted per kind.
+    food_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    prize_instances: Gm<InstancedMesh, PhysicalMaterial>,
+    /// Countdown rings round expiring items, losing beads as time runs out.
+    ring_instances: Gm<InstancedMesh, PhysicalMaterial>,
     particle_system: Gm<InstancedMesh, PhysicalMaterial>,
     particles: Vec<Particle>,
-    grid_size: i32,
+    // Cosmetic only; kept apart from the game's RNG so effects never shift the food sequence
+    particle_rng: Rng,
+    cube: Cube,
+    /// The body as of the previous and latest tick drawn, for easing between them.
+    previous_body: Vec<Position>,
+    current_body: Vec<Position>,
+    body_ticks: u64,
     target_pos: Vec3,
     target_up: Vec3,
     time: f64,
@@ -26,7 +47,7 @@ struct Particle {
 }
 
 impl GameRenderer {
-    pub fn new(context: Context, grid_size: i32) -> Self {
+    pub fn new(context: Context, cube: Cube) -> Self {
         let camera = Camera::new_perspective(
             Viewport::new_at_origo(1, 1),
             vec3(4.0, 4.0, 4.0),
@@ -38,140 +59,54 @@ impl GameRenderer {
         );
         // let control = OrbitControl::new(*camera.target(), 1.0, 100.0);
 
-        // Board Voxels
-        let mut board_transformations = Vec::new();
-        let cell_size = 2.0 / grid_size as f32;
-        let voxel_scale = cell_size * 0.95; // Slightly smaller for gaps
-
-        for x in 0..grid_size {
-            for y in 0..grid_size {
-                for z in 0..grid_size {
-                    // Position
-                    // Map 0..N to -1..1
-                    // Center of voxel i is -1 + (i * cell_size) + cell_size/2
-                    let cx = -1.0 + (x as f32 * cell_size) + cell_size / 2.0;
-                    let cy = -1.0 + (y as f32 * cell_size) + cell_size / 2.0;
-                    let cz = -1.0 + (z as f32 * cell_size) + cell_size / 2.0;
-                    
-                    board_transformations.push(
-                        Mat4::from_translation(vec3(cx, cy, cz)) * Mat4::from_scale(voxel_scale)
-                    );
-                }
-            }
-        }
-
-        let mut board_material = PhysicalMaterial::new(
-            &context,
-            &CpuMaterial {
-                albedo: Srgba::new(50, 50, 200, 50), // Transparent blueish
-                roughness: 0.1,
-                metallic: 0.9,
-                emissive: Srgba::new(0, 0, 50, 255), // Slight glow
-                ..Default::default()
-            },
-        );
-        board_material.render_states.blend = Blend::TRANSPARENCY;
-        board_material.render_states.write_mask = WriteMask::COLOR;
-
-        // Use spheres for rounder look
-        let board_instances = Gm::new(
-            InstancedMesh::new(&context, &Instances {
-                transformations: board_transformations, 
-                ..Default::default()
-            }, &CpuMesh::sphere(16)),
-            board_material,
-        );
-
-        // Grid Lines (3D Beams)
-        let mut grid_transformations = Vec::new();
-        let step = 2.0 / grid_size as f32;
-        let offset = 0.002; // Slightly above surface
-        let thickness = 0.02; // Thickness of the grid lines
-
-        // Helper to add beam
-        let mut add_beam = |pos: Vec3, scale: Vec3| {
-            grid_transformations.push(
-                Mat4::from_translation(pos) * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z)
-            );
-        };
-
-        // Generate grid for each face
-        for i in 0..=grid_size {
-            let t = -1.0 + (i as f32 * step);
-            
-            // Front & Back (z = +/- 1)
-            // Vertical lines
-            add_beam(vec3(t, 0.0, 1.0 + offset), vec3(thickness, 1.0, thickness)); // Front
-            add_beam(vec3(t, 0.0, -1.0 - offset), vec3(thickness, 1.0, thickness)); // Back
-            // Horizontal lines
-            add_beam(vec3(0.0, t, 1.0 + offset), vec3(1.0, thickness, thickness)); // Front
-            add_beam(vec3(0.0, t, -1.0 - offset), vec3(1.0, thickness, thickness)); // Back
-
-            // Left & Right (x = +/- 1)
-            // Vertical lines (y axis)
-            add_beam(vec3(1.0 + offset, 0.0, t), vec3(thickness, 1.0, thickness)); // Right
-            add_beam(vec3(-1.0 - offset, 0.0, t), vec3(thickness, 1.0, thickness)); // Left
-            // Horizontal lines (z axis)
-            add_beam(vec3(1.0 + offset, t, 0.0), vec3(thickness, thickness, 1.0)); // Right
-            add_beam(vec3(-1.0 - offset, t, 0.0), vec3(thickness, thickness, 1.0)); // Left
-
-            // Top & Bottom (y = +/- 1)
-            // Lines along x
-            add_beam(vec3(0.0, 1.0 + offset, t), vec3(1.0, thickness, thickness)); // Top
-            add_beam(vec3(0.0, -1.0 - offset, t), vec3(1.0, thickness, thickness)); // Bottom
-            // Lines along z
-            add_beam(vec3(t, 1.0 + offset, 0.0), vec3(thickness, thickness, 1.0)); // Top
-            add_beam(vec3(t, -1.0 - offset, 0.0), vec3(thickness, thickness, 1.0)); // Bottom
-        }
+        let (board_instances, grid_instances) = build_board(&context, cube);
 
-        let grid_instances = Gm::new(
-            InstancedMesh::new(&context, &Instances {
-                transformations: grid_transformations,
-                ..Default::default()
-            }, &CpuMesh::cube()),
+        // Snake Instances
+        let snake_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(16)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new(0, 255, 255, 255), // Bright Cyan
-                    emissive: Srgba::new(0, 200, 200, 255), // Glowing
-                    roughness: 0.5,
+                    albedo: Srgba::new_opaque(50, 200, 50), // Green snake
+                    emissive: Srgba::new_opaque(50, 150, 50), // Glow
+                    roughness: 0.2,
                     metallic: 0.5,
                     ..Default::default()
                 },
             ),
         );
-        
-        // Snake Instances
-        let snake_instances = Gm::new(
-            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(16)),
+
+        // Obstacle Instances - raised blocks
+        let obstacle_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cube()),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new_opaque(50, 200, 50), // Green snake
-                    emissive: Srgba::new_opaque(50, 150, 50), // Glow
-                    roughness: 0.2,
-                    metallic: 0.5,
+                    albedo: Srgba::new_opaque(120, 120, 140), // Stone grey
+                    emissive: Srgba::new_opaque(30, 30, 60),
+                    roughness: 0.8,
+                    metallic: 0.2,
                     ..Default::default()
                 },
             ),
         );
 
-        // Food Mesh - Sphere
-        let food_mesh = Gm::new(
-            Mesh::new(&context, &CpuMesh::sphere(16)),
+        // Food Instances - Spheres, coloured by kind
+        let food_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(16)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
-                    albedo: Srgba::new_opaque(200, 50, 50), // Red food
-                    emissive: Srgba::new_opaque(100, 0, 0),
+                    albedo: Srgba::WHITE,
+                    emissive: Srgba::new_opaque(60, 30, 30),
                     ..Default::default()
                 },
             ),
         );
 
-        // Prize Mesh - Cylinder (Gold)
-        let prize_mesh = Gm::new(
-            Mesh::new(&context, &CpuMesh::cylinder(16)),
+        // Prize Instances - Cylinders (Gold)
+        let prize_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::cylinder(16)),
             PhysicalMaterial::new(
                 &context,
                 &CpuMaterial {
@@ -184,6 +119,19 @@ impl GameRenderer {
             ),
         );
 
+        // Ring Instances - Beads (Gold)
+        let ring_instances = Gm::new(
+            InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(8)),
+            PhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(255, 215, 0),
+                    emissive: Srgba::new_opaque(150, 120, 0),
+                    ..Default::default()
+                },
+            ),
+        );
+
         // Particle System
         let particle_system = Gm::new(
             InstancedMesh::new(&context, &Instances::default(), &CpuMesh::sphere(8)),
@@ -204,17 +152,37 @@ impl GameRenderer {
             board_instances,
             grid_instances,
             snake_instances,
-            food_mesh,
-            prize_mesh,
+            obstacle_instances,
+            obstacle_cells: Vec::new(),
+            food_instances,
+            prize_instances,
+            ring_instances,
             particle_system,
             particles: Vec::new(),
-            grid_size,
+            // Deliberately unseeded: particles needn't repeat, and nothing that
+            // affects play may draw from this
+            particle_rng: Rng::from_entropy(),
+            cube,
+            previous_body: Vec::new(),
+            current_body: Vec::new(),
+            body_ticks: 0,
             target_pos: vec3(0.0, 0.0, 4.0),
             target_up: vec3(0.0, 1.0, 0.0),
             time: 0.0,
         }
     }
 
+    /// Rebuilds the board for a game on a differently sized cube.
+    fn set_cube(&mut self, cube: Cube) {
+        (self.board_instances, self.grid_instances) = build_board(&self.context, cube);
+        self.cube = cube;
+        // Cached cell positions are in the old cube's coordinates
+        self.obstacle_cells.clear();
+        self.obstacle_instances.geometry.set_instances(&Instances::default());
+        self.current_body.clear();
+        self.previous_body.clear();
+    }
+
     pub fn resize(&mut self, width: u32, height: u32) {
         self.camera.set_viewport(Viewport::new_at_origo(width, height));
     }
@@ -223,6 +191,14 @@ impl GameRenderer {
         // self.control.handle_events(&mut self.camera, events);
     }
 
+    /// The face-local direction matching `screen` as the camera currently shows `face`.
+    pub fn screen_to_direction(&self, face: Face, screen: Direction) -> Direction {
+        let view = self.camera.view_direction();
+        let right = self.camera.right_direction().normalize();
+        let up = right.cross(view).normalize();
+        geometry::screen_to_face_direction(face, right.into(), up.into(), screen)
+    }
+
     pub fn update_camera_target(&mut self, face: Face, distance: f32) {
         let (pos, up) = match face {
             Face::Front => (vec3(0.0, 0.0, distance), vec3(0.0, 1.0, 0.0)),
@@ -237,8 +213,12 @@ impl GameRenderer {
         self.target_up = up;
     }
 
-    pub fn render(&mut self, game: &GameState, target: &RenderTarget, dt: f64) {
+    /// `alpha` is how far the game is towards its next tick, from the scheduler.
+    pub fn render(&mut self, game: &GameState, target: &RenderTarget, dt: f64, alpha: f32) {
         self.time += dt;
+        if game.topology != self.cube {
+            self.set_cube(game.topology);
+        }
 
         // Calculate required distance based on aspect ratio
         let viewport = self.camera.viewport();
@@ -273,7 +253,7 @@ impl GameRenderer {
             100.0,
         );
 
-        let cell_size = 2.0 / self.grid_size as f32;
+        let cell_size = geometry::cell_size(self.cube);
         let offset = 0.05; // Lift off surface
 
         // Detect eat event for particles
@@ -285,10 +265,25 @@ impl GameRenderer {
         // No, let's check if food changed position? No, food respawns.
         // Let's just spawn particles in `lib.rs` by calling a new method on renderer.
 
-        // Update Snake Instances
-        let transformations: Vec<Mat4> = game.snake.body.iter().map(|pos| {
-            let center = self.pos_to_vec3(*pos, cell_size, offset);
-            Mat4::from_translation(center) * Mat4::from_scale(cell_size * 0.6) // Smaller snake
+        // Remember the body at each new tick. Anything other than a few ticks
+        // forward (a new game, a seek, a loaded save) snaps instead of sliding.
+        if game.ticks != self.body_ticks || self.current_body.is_empty() {
+            let advanced = game.ticks.wrapping_sub(self.body_ticks);
+            self.previous_body = if (1..=MAX_CATCH_UP_TICKS as u64).contains(&advanced) {
+                std::mem::take(&mut self.current_body)
+            } else {
+                Vec::new()
+            };
+            self.current_body = game.snake.body().iter().copied().collect();
+            self.body_ticks = game.ticks;
+        }
+
+        // Update Snake Instances, each segment sliding from its previous cell
+        // over the surface, so segments round an edge instead of cutting the corner
+        let transformations: Vec<Mat4> = self.current_body.iter().enumerate().map(|(i, &pos)| {
+            let from = self.previous_body.get(i).copied().unwrap_or(pos);
+            let at = Vec3::from(geometry::slide(self.cube, from, pos, offset, alpha));
+            Mat4::from_translation(at) * Mat4::from_scale(cell_size * 0.6) // Smaller snake
         }).collect();
         
         let instances = Instances {
@@ -297,19 +292,55 @@ impl GameRenderer {
         };
         self.snake_instances.geometry.set_instances(&instances);
 
-        // Update Food Position & Animation
-        let food_pos = self.pos_to_vec3(game.food, cell_size, offset);
+        // Update Obstacle Instances
+        if self.obstacle_cells != game.obstacles {
+            self.obstacle_cells = game.obstacles.clone();
+            let transformations = self.obstacle_cells.iter().map(|&pos| self.obstacle_transform(pos)).collect();
+            self.obstacle_instances.geometry.set_instances(&Instances {
+                transformations,
+                ..Default::default()
+            });
+        }
+
+        // Update Food Positions & Animation
         let bounce = (self.time * 5.0).sin() as f32 * 0.05;
         let rotate = Mat4::from_angle_y(radians((self.time * 2.0) as f32));
-
-        let food_scale = if game.is_prize { cell_size * 0.5 } else { cell_size * 0.4 };
-        let food_transform = Mat4::from_translation(food_pos + vec3(0.0, 0.0, bounce)) * rotate * Mat4::from_scale(food_scale);
-
-        if game.is_prize {
-            self.prize_mesh.set_transformation(food_transform);
-        } else {
-            self.food_mesh.set_transformation(food_transform);
+        let mut food_transformations = Vec::new();
+        let mut food_colors = Vec::new();
+        let mut prize_transformations = Vec::new();
+        let mut ring_transformations = Vec::new();
+        let blink_off = (self.time * 8.0) as i64 % 2 == 0;
+        for food in &game.foods {
+            if let (Some(lifetime), Some(ticks_left)) = (game.food_lifetime(food), game.food_ticks_left(food)) {
+                // Eases round between ticks, like the snake
+                let remaining = ((ticks_left as f32 + 1.0 - alpha) / lifetime as f32).clamp(0.0, 1.0);
+                ring_transformations.extend(self.ring_transforms(food.pos, remaining));
+                // Blink through the last few moves
+                if ticks_left < EXPIRY_WARNING_TICKS && blink_off {
+                    continue;
+                }
+            }
+            let food_pos = self.pos_to_vec3(food.pos, offset) + vec3(0.0, 0.0, bounce);
+            if food.kind == FoodKind::Prize {
+                prize_transformations.push(Mat4::from_translation(food_pos) * rotate * Mat4::from_scale(cell_size * 0.5));
+            } else {
+                food_transformations.push(Mat4::from_translation(food_pos) * rotate * Mat4::from_scale(cell_size * 0.4));
+                food_colors.push(food_color(food.kind));
+            }
         }
+        self.food_instances.geometry.set_instances(&Instances {
+            transformations: food_transformations,
+            colors: Some(food_colors),
+            ..Default::default()
+        });
+        self.prize_instances.geometry.set_instances(&Instances {
+            transformations: prize_transformations,
+            ..Default::default()
+        });
+        self.ring_instances.geometry.set_instances(&Instances {
+            transformations: ring_transformations,
+            ..Default::default()
+        });
 
         // Update Particles
         let mut particle_transformations = Vec::new();
@@ -336,12 +367,22 @@ impl GameRenderer {
         let ambient = AmbientLight::new(&self.context, 0.3, Srgba::WHITE);
         let directional = DirectionalLight::new(&self.context, 2.0, Srgba::WHITE, &vec3(1.0, 1.0, 1.0));
 
-        let point_light_color = if game.is_prize { Srgba::new_opaque(255, 215, 0) } else { Srgba::new_opaque(255, 50, 50) };
+        // Light the item nearest the head
+        let head = self.pos_to_vec3(game.snake.head(), offset);
+        let nearest = game.foods.iter().min_by(|a, b| {
+            let a = self.pos_to_vec3(a.pos, offset).distance2(head);
+            let b = self.pos_to_vec3(b.pos, offset).distance2(head);
+            a.total_cmp(&b)
+        });
+        let (light_pos, light_color) = match nearest {
+            Some(food) => (self.pos_to_vec3(food.pos, offset), food_color(food.kind)),
+            None => (head, Srgba::BLACK),
+        };
         let point_light = PointLight::new(
             &self.context,
             5.0,
-            point_light_color,
-            &food_pos,
+            light_color,
+            &light_pos,
             Attenuation { constant: 0.1, linear: 0.3, quadratic: 0.3 },
         );
 
@@ -352,30 +393,31 @@ impl GameRenderer {
         target.clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0)); // Dark grey
 
         // Render objects
-        let mut objects: Vec<&dyn Object> = vec![&self.board_instances, &self.grid_instances, &self.snake_instances, &self.particle_system];
-        if game.is_prize {
-            objects.push(&self.prize_mesh);
-        } else {
-            objects.push(&self.food_mesh);
-        }
+        let objects: Vec<&dyn Object> = vec![
+            &self.board_instances,
+            &self.grid_instances,
+            &self.obstacle_instances,
+            &self.snake_instances,
+            &self.particle_system,
+            &self.food_instances,
+            &self.prize_instances,
+            &self.ring_instances,
+        ];
 
         target.render(&self.camera, objects.as_slice(), lights);
     }
 
-    pub fn spawn_particles(&mut self, pos: Position, is_prize: bool) {
-        let cell_size = 2.0 / self.grid_size as f32;
+    pub fn spawn_particles(&mut self, pos: Position, kind: FoodKind) {
         let offset = 0.05;
-        let center = self.pos_to_vec3(pos, cell_size, offset);
+        let center = self.pos_to_vec3(pos, offset);
 
-        let color = if is_prize { Srgba::new_opaque(255, 215, 0) } else { Srgba::new_opaque(200, 50, 50) };
+        let color = food_color(kind);
 
         for _ in 0..10 {
              // Simple random velocity
-             let mut rng_buf = [0u8; 3];
-             getrandom::getrandom(&mut rng_buf).unwrap_or(());
-             let rx = (rng_buf[0] as f32 / 255.0) - 0.5;
-             let ry = (rng_buf[1] as f32 / 255.0) - 0.5;
-             let rz = (rng_buf[2] as f32 / 255.0) - 0.5;
+             let rx = self.particle_rng.next_f32() - 0.5;
+             let ry = self.particle_rng.next_f32() - 0.5;
+             let rz = self.particle_rng.next_f32() - 0.5;
              let velocity = vec3(rx, ry, rz).normalize() * 1.0; // Explosion speed
 
              self.particles.push(Particle {
@@ -387,29 +429,163 @@ impl GameRenderer {
         }
     }
 
-    fn pos_to_vec3(&self, pos: Position, cell_size: f32, offset: f32) -> Vec3 {
-        let u = pos.u as f32;
-        let v = pos.v as f32;
-        let half_size = cell_size / 2.0;
-        
-        // Base coordinates on face (from -1 to 1)
-        // u maps to a range. 
-        // 0 -> -1 + half_size
-        // N-1 -> 1 - half_size
-        
-        let u_local = -1.0 + (u * cell_size) + half_size;
-        let v_local = -1.0 + (v * cell_size) + half_size;
-        
-        // Surface level is 1.0 + offset (or -1.0 - offset)
-        let surface = 1.0 + offset;
-
-        match pos.face {
-            Face::Front => vec3(u_local, v_local, surface),
-            Face::Back => vec3(-u_local, v_local, -surface), // Note -u_local to match Right/Left logic
-            Face::Right => vec3(surface, v_local, -u_local),
-            Face::Left => vec3(-surface, v_local, u_local),
-            Face::Top => vec3(u_local, surface, -v_local),
-            Face::Bottom => vec3(u_local, -surface, v_local),
+    /// Beads round the edge of `pos`'s cell, running clockwise from the top
+    /// for the `remaining` fraction of a full ring.
+    fn ring_transforms(&self, pos: Position, remaining: f32) -> Vec<Mat4> {
+        let cell_size = geometry::cell_size(self.cube);
+        let (u, v, _) = geometry::face_axes(pos.face);
+        let center = self.pos_to_vec3(pos, 0.05);
+        let beads = (RING_BEADS as f32 * remaining).ceil() as usize;
+        (0..beads)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / RING_BEADS as f32;
+                let offset = (Vec3::from(u) * angle.sin() + Vec3::from(v) * angle.cos()) * cell_size * 0.45;
+                Mat4::from_translation(center + offset) * Mat4::from_scale(cell_size * 0.06)
+            })
+            .collect()
+    }
+
+    /// A block filling `pos`'s cell, standing up off the face along its normal.
+    fn obstacle_transform(&self, pos: Position) -> Mat4 {
+        let cell_size = geometry::cell_size(self.cube);
+        let half_width = cell_size * 0.45;
+        let half_height = cell_size * 0.4;
+        let (u, v, normal) = geometry::face_axes(pos.face);
+        let center = self.pos_to_vec3(pos, half_height);
+        // The cube mesh spans -1..1, so each axis is scaled by a half size
+        Mat4::from_cols(
+            (Vec3::from(u) * half_width).extend(0.0),
+            (Vec3::from(v) * half_width).extend(0.0),
+            (Vec3::from(normal) * half_height).extend(0.0),
+            center.extend(1.0),
+        )
+    }
+
+    fn pos_to_vec3(&self, pos: Position, offset: f32) -> Vec3 {
+        Vec3::from(geometry::cell_center(self.cube, pos, offset))
+    }
+}
+
+fn food_color(kind: FoodKind) -> Srgba {
+    match kind {
+        FoodKind::Normal => Srgba::new_opaque(200, 50, 50), // Red
+        FoodKind::Prize => Srgba::new_opaque(255, 215, 0), // Gold
+        FoodKind::Shrink => Srgba::new_opaque(160, 60, 220), // Purple
+        FoodKind::SlowDown => Srgba::new_opaque(60, 120, 255), // Blue
+        FoodKind::SpeedUp => Srgba::new_opaque(255, 140, 0), // Orange
+        FoodKind::BonusTime => Srgba::new_opaque(60, 220, 220), // Cyan
+        FoodKind::PowerUp(PowerUp::Ghost) => Srgba::new_opaque(230, 230, 255), // Pale white
+        FoodKind::PowerUp(PowerUp::Magnet) => Srgba::new_opaque(255, 60, 160), // Magenta
+        FoodKind::PowerUp(PowerUp::SlowMo) => Srgba::new_opaque(150, 150, 255), // Lavender
+        FoodKind::PowerUp(PowerUp::DoubleScore) => Srgba::new_opaque(255, 255, 120), // Pale yellow
+    }
+}
+
+/// The translucent voxel board and its grid lines for `cube`.
+fn build_board(context: &Context, cube: Cube) -> (Gm<InstancedMesh, PhysicalMaterial>, Gm<InstancedMesh, PhysicalMaterial>) {
+    // Board Voxels
+    let mut board_transformations = Vec::new();
+    let cell_size = geometry::cell_size(cube);
+    let half = Vec3::from(geometry::half_extents(cube));
+    let voxel_scale = cell_size * 0.95; // Slightly smaller for gaps
+
+    for x in 0..cube.width {
+        for y in 0..cube.height {
+            for z in 0..cube.depth {
+                // Position
+                // Map 0..N to -half..half
+                // Center of voxel i is -half + (i * cell_size) + cell_size/2
+                let cx = -half.x + (x as f32 * cell_size) + cell_size / 2.0;
+                let cy = -half.y + (y as f32 * cell_size) + cell_size / 2.0;
+                let cz = -half.z + (z as f32 * cell_size) + cell_size / 2.0;
+                
+                board_transformations.push(
+                    Mat4::from_translation(vec3(cx, cy, cz)) * Mat4::from_scale(voxel_scale)
+                );
+            }
         }
     }
+
+    let mut board_material = PhysicalMaterial::new(
+        context,
+        &CpuMaterial {
+            albedo: Srgba::new(50, 50, 200, 50), // Transparent blueish
+            roughness: 0.1,
+            metallic: 0.9,
+            emissive: Srgba::new(0, 0, 50, 255), // Slight glow
+            ..Default::default()
+        },
+    );
+    board_material.render_states.blend = Blend::TRANSPARENCY;
+    board_material.render_states.write_mask = WriteMask::COLOR;
+
+    // Use spheres for rounder look
+    let board_instances = Gm::new(
+        InstancedMesh::new(context, &Instances {
+            transformations: board_transformations, 
+            ..Default::default()
+        }, &CpuMesh::sphere(16)),
+        board_material,
+    );
+
+    // Grid Lines (3D Beams)
+    let mut grid_transformations = Vec::new();
+    let offset = 0.002; // Slightly above surface
+    let thickness = 0.02; // Thickness of the grid lines
+
+    // Helper to add beam
+    let mut add_beam = |pos: Vec3, scale: Vec3| {
+        grid_transformations.push(
+            Mat4::from_translation(pos) * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+        );
+    };
+
+    // Generate grid for each face; the beam mesh spans -1..1, so scale is a half length
+    let (sx, sy, sz) = (half.x + offset, half.y + offset, half.z + offset);
+
+    // Lines at constant x
+    for i in 0..=cube.width {
+        let t = -half.x + (i as f32 * cell_size);
+        add_beam(vec3(t, 0.0, sz), vec3(thickness, half.y, thickness)); // Front
+        add_beam(vec3(t, 0.0, -sz), vec3(thickness, half.y, thickness)); // Back
+        add_beam(vec3(t, sy, 0.0), vec3(thickness, thickness, half.z)); // Top
+        add_beam(vec3(t, -sy, 0.0), vec3(thickness, thickness, half.z)); // Bottom
+    }
+
+    // Lines at constant y
+    for i in 0..=cube.height {
+        let t = -half.y + (i as f32 * cell_size);
+        add_beam(vec3(0.0, t, sz), vec3(half.x, thickness, thickness)); // Front
+        add_beam(vec3(0.0, t, -sz), vec3(half.x, thickness, thickness)); // Back
+        add_beam(vec3(sx, t, 0.0), vec3(thickness, thickness, half.z)); // Right
+        add_beam(vec3(-sx, t, 0.0), vec3(thickness, thickness, half.z)); // Left
+    }
+
+    // Lines at constant z
+    for i in 0..=cube.depth {
+        let t = -half.z + (i as f32 * cell_size);
+        add_beam(vec3(sx, 0.0, t), vec3(thickness, half.y, thickness)); // Right
+        add_beam(vec3(-sx, 0.0, t), vec3(thickness, half.y, thickness)); // Left
+        add_beam(vec3(0.0, sy, t), vec3(half.x, thickness, thickness)); // Top
+        add_beam(vec3(0.0, -sy, t), vec3(half.x, thickness, thickness)); // Bottom
+    }
+
+    let grid_instances = Gm::new(
+        InstancedMesh::new(context, &Instances {
+            transformations: grid_transformations,
+            ..Default::default()
+        }, &CpuMesh::cube()),
+        PhysicalMaterial::new(
+            context,
+            &CpuMaterial {
+                albedo: Srgba::new(0, 255, 255, 255), // Bright Cyan
+                emissive: Srgba::new(0, 200, 200, 255), // Glowing
+                roughness: 0.5,
+                metallic: 0.5,
+                ..Default::default()
+            },
+        ),
+    );
+
+    (board_instances, grid_instances)
 }
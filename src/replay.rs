@@ -0,0 +1,304 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::food::{self, FoodTable};
+use crate::game::{GameConfig, GameEvent, GameState, Steer};
+use crate::obstacles::ObstacleLayout;
+
+/// Bumped whenever a change to the simulation would make old replays play back differently.
+/// Version 2 added expiring prizes.
+pub const REPLAY_VERSION: u32 = 2;
+
+/// Oldest version that still plays back as it was recorded: version 1
+/// replays leave the prize lifetime unset, so their prizes never expire.
+pub const MIN_REPLAY_VERSION: u32 = 1;
+
+/// A steering input and the tick it was queued before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedInput {
+    pub tick: u64,
+    pub steer: Steer,
+}
+
+/// Everything needed to re-simulate a game: its seed, config and inputs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub config: GameConfig,
+    pub inputs: Vec<TimedInput>,
+    /// How many ticks the game ran for.
+    pub ticks: u64,
+}
+
+impl Replay {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Re-simulates a replay tick by tick, with pause, seek and speed controls.
+pub struct ReplayPlayer {
+    replay: Replay,
+    game: GameState,
+    next_input: usize,
+    pub paused: bool,
+    /// Playback speed as a multiple of normal.
+    pub speed: f64,
+}
+
+impl ReplayPlayer {
+    pub const MIN_SPEED: f64 = 0.25;
+    pub const MAX_SPEED: f64 = 8.0;
+
+    pub fn new(replay: Replay) -> Self {
+        let game = GameState::with_config(replay.config.clone(), replay.seed);
+        Self {
+            replay,
+            game,
+            next_input: 0,
+            paused: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.game.ticks
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.game.game_over || self.game.ticks >= self.replay.ticks
+    }
+
+    /// Advances one tick, feeding in the inputs recorded for it. `None` once
+    /// the replay is over.
+    pub fn step(&mut self) -> Option<Vec<GameEvent>> {
+        if self.is_finished() {
+            return None;
+        }
+        while let Some(input) = self.replay.inputs.get(self.next_input) {
+            if input.tick > self.game.ticks {
+                break;
+            }
+            self.game.steer(input.steer);
+            self.next_input += 1;
+        }
+        Some(self.game.update())
+    }
+
+    /// Jumps to `tick`, re-simulating from the start when going backwards.
+    pub fn seek(&mut self, tick: u64) {
+        if tick < self.game.ticks {
+            self.game = GameState::with_config(self.replay.config.clone(), self.replay.seed);
+            self.next_input = 0;
+        }
+        while self.game.ticks < tick && self.step().is_some() {}
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(Self::MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(Self::MIN_SPEED);
+    }
+}
+
+/// Largest board side a submitted replay may use, to bound verification cost.
+pub const MAX_VERIFIED_SIDE: i32 = 64;
+
+/// Longest a submitted replay may run, in moves per cell of its board. Filling
+/// the board takes one item per cell, each a short run away, so honest games
+/// come nowhere near this; it only stops a claimed tick count from stalling
+/// verification.
+pub const MAX_VERIFIED_TICKS_PER_CELL: u64 = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    UnsupportedVersion(u32),
+    /// Not a stock endless game, board size out of range, a game too long
+    /// to check, inputs out of order, or inputs the game would not have
+    /// accepted.
+    Malformed,
+    ScoreMismatch { claimed: u32, actual: u32 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::UnsupportedVersion(v) => write!(f, "unsupported replay version {v}"),
+            VerifyError::Malformed => write!(f, "malformed replay"),
+            VerifyError::ScoreMismatch { claimed, actual } => {
+                write!(f, "claimed score {claimed} but the replay scores {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Whether `config` is an endless game as the menu starts one: a plain cube
+/// at one of the preset difficulties, with one item at a time from the
+/// classic table. Leaderboards are only keyed on difficulty (and check the
+/// board size themselves), so anything else (obstacles, a scripted start or
+/// food, a target or time limit, a board full of food) would let a score
+/// from an easier game onto the board. Prizes expire as they did in the
+/// replay's `version`.
+fn is_stock_endless(config: &GameConfig, version: u32) -> bool {
+    let prize_lifetime = if version == 1 { None } else { Some(food::PRIZE_LIFETIME) };
+    !matches!(config.difficulty, Difficulty::Custom(_))
+        && config.height == config.width
+        && config.depth == config.width
+        && config.obstacles == ObstacleLayout::None
+        && config.start.is_none()
+        && config.food_script.is_empty()
+        && config.target_score.is_none()
+        && config.time_limit.is_none()
+        && config.food_count == 1
+        && config.food_table == FoodTable::Classic
+        && config.prize_lifetime == prize_lifetime
+}
+
+/// Re-simulates `replay` headlessly and checks that it scores `claimed`.
+pub fn verify_score(replay: &Replay, claimed: u32) -> Result<(), VerifyError> {
+    if !(MIN_REPLAY_VERSION..=REPLAY_VERSION).contains(&replay.version) {
+        return Err(VerifyError::UnsupportedVersion(replay.version));
+    }
+    let config = &replay.config;
+    if !is_stock_endless(config, replay.version) {
+        return Err(VerifyError::Malformed);
+    }
+    let sides = [config.width, config.height, config.depth];
+    if sides.iter().any(|side| !(1..=MAX_VERIFIED_SIDE).contains(side)) {
+        return Err(VerifyError::Malformed);
+    }
+    let [width, height, depth] = sides.map(|side| side as u64);
+    let cells = 2 * (width * height + width * depth + height * depth);
+    if replay.ticks > cells * MAX_VERIFIED_TICKS_PER_CELL {
+        return Err(VerifyError::Malformed);
+    }
+    if replay.inputs.windows(2).any(|w| w[0].tick > w[1].tick) {
+        return Err(VerifyError::Malformed);
+    }
+
+    let mut player = ReplayPlayer::new(replay.clone());
+    while player.step().is_some() {}
+
+    // Every input must have been taken, at the tick it claims, and the game
+    // must have lasted as long as the replay says
+    let resimulated = player.game().replay();
+    if resimulated.inputs != replay.inputs || resimulated.ticks != replay.ticks {
+        return Err(VerifyError::Malformed);
+    }
+    let actual = player.game().score;
+    if actual != claimed {
+        return Err(VerifyError::ScoreMismatch { claimed, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::food::{FoodKind, FoodWeight};
+    use crate::game::{Direction, Turn};
+    use crate::powerups::PowerUp;
+
+    fn play_recorded_game() -> GameState {
+        play_recorded_game_with(GameConfig::cube(6))
+    }
+
+    fn play_recorded_game_with(config: GameConfig) -> GameState {
+        let mut game = GameState::with_config(config, 99);
+        let script = [
+            (3, Steer::Absolute(Direction::Left)),
+            (5, Steer::Absolute(Direction::Down)),
+            (9, Steer::Relative(Turn::Left)),
+            (14, Steer::Relative(Turn::Left)),
+            (20, Steer::Absolute(Direction::Up)),
+        ];
+        for tick in 0..60 {
+            for &(at, steer) in &script {
+                if at == tick {
+                    game.steer(steer);
+                }
+            }
+            game.update();
+            if game.game_over {
+                break;
+            }
+        }
+        game
+    }
+
+    #[test]
+    fn test_replay_reproduces_game() {
+        let game = play_recorded_game();
+        assert!(game.ticks > 20, "scripted game should outlast its inputs");
+        let replay = Replay::from_json(&game.replay().to_json().unwrap()).unwrap();
+
+        let mut player = ReplayPlayer::new(replay);
+        while player.step().is_some() {}
+        assert_eq!(player.game().snake.body(), game.snake.body());
+        assert_eq!(player.game().score, game.score);
+        assert_eq!(player.game().foods, game.foods);
+        assert_eq!(player.tick(), game.ticks);
+    }
+
+    #[test]
+    fn test_replay_reproduces_power_ups() {
+        let weights = [PowerUp::Ghost, PowerUp::Magnet, PowerUp::SlowMo, PowerUp::DoubleScore]
+            .map(|power| FoodWeight { kind: FoodKind::PowerUp(power), weight: 1 });
+        let config = GameConfig { food_count: 30, food_table: FoodTable::Weighted(weights.to_vec()), ..GameConfig::cube(6) };
+        let game = play_recorded_game_with(config);
+        assert!(game.food_eaten_count > 0, "scripted game should pick something up");
+
+        let mut player = ReplayPlayer::new(game.replay());
+        while player.step().is_some() {}
+        assert_eq!(player.game().effects, game.effects);
+        assert_eq!(player.game().foods, game.foods);
+        assert_eq!(player.game().elapsed, game.elapsed);
+        assert_eq!(verify_score(&game.replay(), game.score), Err(VerifyError::Malformed), "not an endless game");
+    }
+
+    #[test]
+    fn test_replay_from_before_prizes_expired_verifies() {
+        let game = play_recorded_game_with(GameConfig { prize_lifetime: None, ..GameConfig::cube(6) });
+        let mut replay = game.replay();
+        assert_eq!(verify_score(&replay, game.score), Err(VerifyError::Malformed), "current replays need expiring prizes");
+        replay.version = 1;
+        assert_eq!(verify_score(&replay, game.score), Ok(()));
+
+        let expiring = play_recorded_game();
+        let mut replay = expiring.replay();
+        replay.version = 1;
+        assert_eq!(verify_score(&replay, expiring.score), Err(VerifyError::Malformed), "version 1 prizes never expired");
+    }
+
+    #[test]
+    fn test_seek_backwards_matches_straight_playback() {
+        let replay = play_recorded_game().replay();
+        let mut straight = ReplayPlayer::new(replay.clone());
+        straight.seek(12);
+
+        let mut seeking = ReplayPlayer::new(replay);
+        seeking.seek(30);
+        seeking.seek(12);
+        assert_eq!(seeking.tick(), 12);
+        assert_eq!(seeking.game().snake.body(), straight.game().snake.body());
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Small seedable PRNG (SplitMix64).
+///
+/// Every random decision in the game goes through one of these, so a seed
+/// fully determines a game given the same inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seeds from the platform entropy source (`crypto.getRandomValues` on the web).
+    pub fn from_entropy() -> Self {
+        Self::new(entropy_seed())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..n`, without modulo bias. `n` must be non-zero.
+    pub fn below(&mut self, n: u32) -> u32 {
+        debug_assert!(n > 0);
+        // Lemire's multiply-shift with rejection of the biased low zone
+        let n = n as u64;
+        let threshold = (u32::MAX as u64 + 1 - n) % n;
+        loop {
+            let x = self.next_u64() >> 32;
+            let m = x * n;
+            if (m & 0xFFFF_FFFF) >= threshold {
+                return (m >> 32) as u32;
+            }
+        }
+    }
+
+    /// Uniform float in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// A fresh seed for games that don't ask for a specific one.
+pub fn entropy_seed() -> u64 {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf).unwrap_or(());
+    u64::from_le_bytes(buf)
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::food::Food;
+use crate::game::{Direction, GameConfig, GameState, Position, Steer};
+use crate::powerups::Effects;
+use crate::replay::TimedInput;
+use crate::rng::Rng;
+
+/// Bumped whenever `Snapshot`'s shape changes. Saves from other versions
+/// are refused rather than half-loaded.
+pub const SAVE_VERSION: u32 = 1;
+
+/// An in-progress game, tagged with the schema it was written with.
+#[derive(Serialize, Deserialize)]
+struct SaveGame<G> {
+    version: u32,
+    game: G,
+}
+
+/// Just enough of a save to decide whether the rest can be read.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// What a save holds of a game: the config and seed it started from, and
+/// what play has changed since. The board, its walls and which cells are
+/// taken all follow from these, so they're rebuilt on load rather than
+/// trusted from the save.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    pub config: GameConfig,
+    pub seed: u64,
+    pub rng: Rng,
+    /// Snake segments, head first.
+    pub body: Vec<Position>,
+    pub direction: Direction,
+    pub turns: Vec<Steer>,
+    pub foods: Vec<Food>,
+    pub food_spawned: u32,
+    pub score: u32,
+    pub high_score: u32,
+    pub food_eaten_count: u32,
+    pub ticks: u64,
+    pub elapsed: f64,
+    pub pace: f64,
+    pub bonus_time: f64,
+    pub effects: Effects,
+    pub game_over: bool,
+    pub won: bool,
+    pub input_log: Vec<TimedInput>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    UnsupportedVersion(u32),
+    /// Unreadable, or describing a game that couldn't happen on its board.
+    Corrupt(serde_json::Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::UnsupportedVersion(v) => write!(f, "unsupported save version {v}"),
+            LoadError::Corrupt(err) => write!(f, "corrupt save: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn to_json(game: &GameState) -> serde_json::Result<String> {
+    serde_json::to_string(&SaveGame { version: SAVE_VERSION, game: game.snapshot() })
+}
+
+pub fn from_json(json: &str) -> Result<GameState, LoadError> {
+    let header: SaveHeader = serde_json::from_str(json).map_err(LoadError::Corrupt)?;
+    if header.version != SAVE_VERSION {
+        return Err(LoadError::UnsupportedVersion(header.version));
+    }
+    let save: SaveGame<Snapshot> = serde_json::from_str(json).map_err(LoadError::Corrupt)?;
+    GameState::restore(save.game).map_err(|reason| LoadError::Corrupt(serde::de::Error::custom(reason)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obstacles::{ObstacleLayout, Symmetry};
+    use crate::powerups::PowerUp;
+
+    #[test]
+    fn test_resumed_game_plays_on_identically() {
+        let mut game = GameState::with_seed(6, 42);
+        game.queue_turn(Direction::Left);
+        for _ in 0..5 {
+            game.update();
+        }
+        game.queue_turn(Direction::Down);
+
+        let mut resumed = from_json(&to_json(&game).unwrap()).unwrap();
+        for _ in 0..30 {
+            let event = game.update();
+            assert!(resumed.update() == event);
+        }
+        assert_eq!(resumed.snake.body(), game.snake.body());
+        assert_eq!(resumed.foods, game.foods);
+        assert_eq!(resumed.score, game.score);
+        assert_eq!(resumed.replay(), game.replay());
+
+        // Walls aren't saved, but come back the same from the config and seed
+        let layout = ObstacleLayout::Generated { density: 0.2, symmetry: Symmetry::None };
+        let walled = GameState::with_config(GameConfig { obstacles: layout, ..GameConfig::cube(6) }, 7);
+        assert!(!walled.obstacles.is_empty());
+        assert_eq!(from_json(&to_json(&walled).unwrap()).unwrap().obstacles, walled.obstacles);
+    }
+
+    #[test]
+    fn test_other_versions_are_refused() {
+        let json = to_json(&GameState::with_seed(6, 1)).unwrap();
+        let future = json.replacen(&format!("\"version\":{SAVE_VERSION}"), "\"version\":99", 1);
+        assert!(matches!(from_json(&future), Err(LoadError::UnsupportedVersion(99))));
+        assert!(matches!(from_json("{\"version\":1"), Err(LoadError::Corrupt(_))));
+    }
+
+    #[test]
+    fn test_impossible_state_is_refused() {
+        let mut game = GameState::with_seed(6, 3);
+        game.update();
+        assert!(GameState::restore(game.snapshot()).is_ok());
+
+        let mut turns = game.snapshot();
+        turns.turns = vec![Steer::Absolute(Direction::Left); 10];
+        let mut pace = game.snapshot();
+        pace.pace = f64::NAN;
+        let mut elapsed = game.snapshot();
+        elapsed.elapsed = -1.0;
+        let mut effects = game.snapshot();
+        effects.effects.grant(PowerUp::Ghost, 1_000_000);
+        for snapshot in [turns, pace, elapsed, effects] {
+            assert!(GameState::restore(snapshot).is_err());
+        }
+    }
+
+    #[test]
+    fn test_positions_off_the_board_are_refused() {
+        let mut game = GameState::with_seed(6, 3);
+        game.update();
+        let json = to_json(&game).unwrap();
+        let head = game.snake.head();
+        let moved = |to: &str| {
+            let from = format!("\"body\":[{{\"face\":\"{:?}\",\"u\":{},\"v\":{}", head.face, head.u, head.v);
+            assert!(json.contains(&from));
+            json.replacen(&from, &format!("\"body\":[{{\"face\":\"{:?}\",\"u\":{to},\"v\":{}", head.face, head.v), 1)
+        };
+        assert!(from_json(&moved("5")).is_ok());
+        assert!(matches!(from_json(&moved("6")), Err(LoadError::Corrupt(_))));
+        assert!(matches!(from_json(&moved("-1")), Err(LoadError::Corrupt(_))));
+
+        let shrunk = json.replacen("\"width\":6", "\"width\":0", 1);
+        assert!(matches!(from_json(&shrunk), Err(LoadError::Corrupt(_))));
+    }
+}
@@ -0,0 +1,121 @@
+/// Most ticks run in a single frame. A longer stall than this drops the rest
+/// of the backlog instead of fast-forwarding the snake into something.
+pub const MAX_CATCH_UP_TICKS: u32 = 4;
+
+/// Turns variable frame times into fixed-length game ticks.
+///
+/// Time left over after a tick carries into the next frame, so the game runs
+/// at the same speed whatever the display's refresh rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TickScheduler {
+    /// Seconds banked towards the next tick.
+    accumulator: f64,
+    ticks_this_frame: u32,
+    max_catch_up: u32,
+}
+
+impl Default for TickScheduler {
+    fn default() -> Self {
+        Self::new(MAX_CATCH_UP_TICKS)
+    }
+}
+
+impl TickScheduler {
+    pub fn new(max_catch_up: u32) -> Self {
+        Self {
+            accumulator: 0.0,
+            ticks_this_frame: 0,
+            max_catch_up,
+        }
+    }
+
+    /// Banks a frame's elapsed time, in seconds.
+    pub fn begin_frame(&mut self, dt: f64) {
+        self.accumulator += dt.max(0.0);
+        self.ticks_this_frame = 0;
+    }
+
+    /// Whether another tick of `interval` seconds is due this frame. Call in
+    /// a loop, running one tick per `true`; the interval may change between
+    /// ticks as the game speeds up.
+    pub fn next_tick(&mut self, interval: f64) -> bool {
+        if self.accumulator < interval {
+            return false;
+        }
+        if self.ticks_this_frame >= self.max_catch_up {
+            // Too far behind; keep only the partial tick
+            self.accumulator %= interval;
+            return false;
+        }
+        self.accumulator -= interval;
+        self.ticks_this_frame += 1;
+        true
+    }
+
+    /// How far into the next tick of `interval` seconds we are, from 0 to 1,
+    /// for drawing between the last two game states.
+    pub fn alpha(&self, interval: f64) -> f32 {
+        (self.accumulator / interval).clamp(0.0, 1.0) as f32
+    }
+
+    /// Forgets any banked time, e.g. when a new game starts.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `seconds` of frames at `hz` and counts the ticks.
+    fn ticks_at(hz: f64, seconds: f64, interval: f64) -> u32 {
+        let mut scheduler = TickScheduler::default();
+        let frames = (seconds * hz).round() as u32;
+        let mut ticks = 0;
+        for _ in 0..frames {
+            scheduler.begin_frame(1.0 / hz);
+            while scheduler.next_tick(interval) {
+                ticks += 1;
+            }
+        }
+        ticks
+    }
+
+    #[test]
+    fn test_tick_rate_is_independent_of_frame_rate() {
+        // 10 seconds at 0.15s per tick is 66 ticks, whatever the display
+        for hz in [30.0, 60.0, 144.0, 7.0] {
+            let ticks = ticks_at(hz, 10.0, 0.15);
+            assert!((65..=67).contains(&ticks), "{hz} Hz ran {ticks} ticks");
+        }
+    }
+
+    #[test]
+    fn test_long_frame_catch_up_is_capped() {
+        let mut scheduler = TickScheduler::new(3);
+        scheduler.begin_frame(2.0);
+        let mut ticks = 0;
+        while scheduler.next_tick(0.1) {
+            ticks += 1;
+        }
+        assert_eq!(ticks, 3);
+
+        // The dropped backlog doesn't come back next frame
+        scheduler.begin_frame(0.0);
+        assert!(!scheduler.next_tick(0.1));
+    }
+
+    #[test]
+    fn test_alpha_tracks_progress_into_next_tick() {
+        let mut scheduler = TickScheduler::default();
+        scheduler.begin_frame(0.25);
+        assert!(scheduler.next_tick(0.1));
+        assert!(scheduler.next_tick(0.1));
+        assert!(!scheduler.next_tick(0.1));
+        assert!((scheduler.alpha(0.1) - 0.5).abs() < 1e-4);
+
+        scheduler.reset();
+        assert_eq!(scheduler.alpha(0.1), 0.0);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::campaign::CampaignProgress;
+use crate::difficulty::Difficulty;
+
+const CONTROL_SCHEME_KEY: &str = "snake3d_control_scheme";
+const DIFFICULTY_KEY: &str = "snake3d_difficulty";
+const CAMPAIGN_KEY: &str = "snake3d_campaign";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// Arrows, buttons and swipes pick a direction on the face.
+    Absolute,
+    /// Only left/right, turning relative to the snake's heading.
+    Relative,
+}
+
+impl ControlScheme {
+    pub fn toggled(self) -> Self {
+        match self {
+            ControlScheme::Absolute => ControlScheme::Relative,
+            ControlScheme::Relative => ControlScheme::Absolute,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ControlScheme::Absolute => "absolute",
+            ControlScheme::Relative => "relative",
+        }
+    }
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn load_control_scheme() -> ControlScheme {
+    match storage().and_then(|s| s.get_item(CONTROL_SCHEME_KEY).ok().flatten()).as_deref() {
+        Some("relative") => ControlScheme::Relative,
+        _ => ControlScheme::Absolute,
+    }
+}
+
+pub fn save_control_scheme(scheme: ControlScheme) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(CONTROL_SCHEME_KEY, scheme.as_str());
+    }
+}
+
+/// Updates the toggle button, key hint and on-screen pad for `scheme`.
+pub fn update_control_scheme_ui(scheme: ControlScheme) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let relative = scheme == ControlScheme::Relative;
+
+    if let Some(btn) = document.get_element_by_id("controls-btn") {
+        btn.set_text_content(Some(if relative { "🎮 Steering" } else { "🎮 Arrows" }));
+    }
+    if let Some(hint) = document.get_element_by_id("controls-hint") {
+        hint.set_text_content(Some(if relative {
+            "A/D / Left/Right to Turn | P to Pause | C to Switch Controls | R to Restart"
+        } else {
+            "WASD / Arrows to Move | P to Pause | C to Switch Controls | R to Restart"
+        }));
+    }
+    if let Some(pad) = document.get_element_by_id("mobile-controls") {
+        pad.class_list().toggle_with_force("relative", relative).unwrap_or(false);
+    }
+}
+
+pub fn load_difficulty() -> Difficulty {
+    storage()
+        .and_then(|s| s.get_item(DIFFICULTY_KEY).ok().flatten())
+        .and_then(|key| Difficulty::from_key(&key))
+        .unwrap_or_default()
+}
+
+pub fn save_difficulty(difficulty: Difficulty) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(DIFFICULTY_KEY, difficulty.key());
+    }
+}
+
+pub fn update_difficulty_ui(difficulty: Difficulty) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(btn) = document.get_element_by_id("difficulty-btn") {
+        btn.set_text_content(Some(&format!("⚡ {}", difficulty.label())));
+    }
+}
+
+pub fn load_campaign_progress() -> CampaignProgress {
+    storage()
+        .and_then(|s| s.get_item(CAMPAIGN_KEY).ok().flatten())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_campaign_progress(progress: CampaignProgress) {
+    if let (Some(storage), Ok(json)) = (storage(), serde_json::to_string(&progress)) {
+        let _ = storage.set_item(CAMPAIGN_KEY, &json);
+    }
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Direction, Face, Position};
+
+/// The play surface: which cells exist and how they connect.
+///
+/// `GameState` is generic over this so new surfaces can be tried without
+/// touching the movement code.
+pub trait Topology {
+    /// The cell reached by moving one step from `pos` heading `dir`, and the
+    /// heading after the step (crossing an edge may rotate it).
+    /// `None` if the step runs into a wall.
+    fn step(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)>;
+
+    /// Number of cells along u and v on `face`; `(0, 0)` for unused faces.
+    fn extent(&self, face: Face) -> (i32, i32);
+
+    /// Whether `pos` is a cell on the surface.
+    fn contains(&self, pos: Position) -> bool {
+        let (w, h) = self.extent(pos.face);
+        (0..w).contains(&pos.u) && (0..h).contains(&pos.v)
+    }
+
+    /// Every cell on the surface.
+    fn cells(&self) -> Vec<Position> {
+        let mut cells = Vec::new();
+        for face in Face::ALL {
+            let (w, h) = self.extent(face);
+            for u in 0..w {
+                for v in 0..h {
+                    cells.push(Position { face, u, v });
+                }
+            }
+        }
+        cells
+    }
+}
+
+fn offset(pos: Position, dir: Direction) -> (i32, i32) {
+    match dir {
+        Direction::Up => (pos.u, pos.v + 1),
+        Direction::Down => (pos.u, pos.v - 1),
+        Direction::Left => (pos.u - 1, pos.v),
+        Direction::Right => (pos.u + 1, pos.v),
+    }
+}
+
+/// The six faces of a box `width` × `height` × `depth` cells (x, y, z).
+///
+/// Unfolded, with each face seen from outside the box:
+///
+/// ```text
+///        Top
+/// Left  Front  Right  Back
+///       Bottom
+/// ```
+///
+/// On the four side faces v points up. Top's v points away from Front,
+/// Bottom's v points towards Front. Front/Back are width × height cells,
+/// Left/Right depth × height, Top/Bottom width × depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cube {
+    pub width: i32,
+    pub height: i32,
+    pub depth: i32,
+}
+
+impl Cube {
+    pub fn new(size: i32) -> Self {
+        Self::cuboid(size, size, size)
+    }
+
+    pub fn cuboid(width: i32, height: i32, depth: i32) -> Self {
+        Self { width, height, depth }
+    }
+}
+
+impl Topology for Cube {
+    fn step(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)> {
+        let (w, h, d) = (self.width, self.height, self.depth);
+        let (nu, nv) = self.extent(pos.face);
+        let (mut u, mut v) = offset(pos, dir);
+        let mut face = pos.face;
+        let mut new_dir = dir;
+
+        if u < 0 || u >= nu || v < 0 || v >= nv {
+            let old_u = pos.u;
+            let old_v = pos.v;
+            match (face, dir) {
+                // Front: straight onto its four neighbours
+                (Face::Front, Direction::Up) => { face = Face::Top; v = 0; }
+                (Face::Front, Direction::Down) => { face = Face::Bottom; v = d - 1; }
+                (Face::Front, Direction::Left) => { face = Face::Left; u = d - 1; }
+                (Face::Front, Direction::Right) => { face = Face::Right; u = 0; }
+
+                // Back: Back's left edge is Right's right edge, and Back's top edge
+                // meets Top's top edge, so u is mirrored going over Top/Bottom.
+                (Face::Back, Direction::Up) => { face = Face::Top; u = w - 1 - old_u; v = d - 1; new_dir = Direction::Down; }
+                (Face::Back, Direction::Down) => { face = Face::Bottom; u = w - 1 - old_u; v = 0; new_dir = Direction::Up; }
+                (Face::Back, Direction::Left) => { face = Face::Right; u = d - 1; }
+                (Face::Back, Direction::Right) => { face = Face::Left; u = 0; }
+
+                // Top: its side edges are the top edges of Left/Right
+                (Face::Top, Direction::Up) => { face = Face::Back; u = w - 1 - old_u; v = h - 1; new_dir = Direction::Down; }
+                (Face::Top, Direction::Down) => { face = Face::Front; v = h - 1; }
+                (Face::Top, Direction::Left) => { face = Face::Left; u = d - 1 - old_v; v = h - 1; new_dir = Direction::Down; }
+                (Face::Top, Direction::Right) => { face = Face::Right; u = old_v; v = h - 1; new_dir = Direction::Down; }
+
+                // Bottom: its side edges are the bottom edges of Left/Right
+                (Face::Bottom, Direction::Up) => { face = Face::Front; v = 0; }
+                (Face::Bottom, Direction::Down) => { face = Face::Back; u = w - 1 - old_u; v = 0; new_dir = Direction::Up; }
+                (Face::Bottom, Direction::Left) => { face = Face::Left; u = old_v; v = 0; new_dir = Direction::Up; }
+                (Face::Bottom, Direction::Right) => { face = Face::Right; u = d - 1 - old_v; v = 0; new_dir = Direction::Up; }
+
+                // Right: enters Top/Bottom through their right edge
+                (Face::Right, Direction::Up) => { face = Face::Top; u = w - 1; v = old_u; new_dir = Direction::Left; }
+                (Face::Right, Direction::Down) => { face = Face::Bottom; u = w - 1; v = d - 1 - old_u; new_dir = Direction::Left; }
+                (Face::Right, Direction::Left) => { face = Face::Front; u = w - 1; }
+                (Face::Right, Direction::Right) => { face = Face::Back; u = 0; }
+
+                // Left: enters Top/Bottom through their left edge
+                (Face::Left, Direction::Up) => { face = Face::Top; u = 0; v = d - 1 - old_u; new_dir = Direction::Right; }
+                (Face::Left, Direction::Down) => { face = Face::Bottom; u = 0; v = old_u; new_dir = Direction::Right; }
+                (Face::Left, Direction::Left) => { face = Face::Back; u = w - 1; }
+                (Face::Left, Direction::Right) => { face = Face::Front; u = 0; }
+            }
+        }
+
+        Some((Position { face, u, v }, new_dir))
+    }
+
+    fn extent(&self, face: Face) -> (i32, i32) {
+        match face {
+            Face::Front | Face::Back => (self.width, self.height),
+            Face::Left | Face::Right => (self.depth, self.height),
+            Face::Top | Face::Bottom => (self.width, self.depth),
+        }
+    }
+}
+
+/// A single rectangular board on the Front face, wrapping around both edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Torus {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Topology for Torus {
+    fn step(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)> {
+        let (u, v) = offset(pos, dir);
+        let u = u.rem_euclid(self.width);
+        let v = v.rem_euclid(self.height);
+        Some((Position { face: pos.face, u, v }, dir))
+    }
+
+    fn extent(&self, face: Face) -> (i32, i32) {
+        match face {
+            Face::Front => (self.width, self.height),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// A single rectangular board on the Front face, surrounded by walls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Walled {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Topology for Walled {
+    fn step(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)> {
+        let (u, v) = offset(pos, dir);
+        if u < 0 || u >= self.width || v < 0 || v >= self.height {
+            return None;
+        }
+        Some((Position { face: pos.face, u, v }, dir))
+    }
+
+    fn extent(&self, face: Face) -> (i32, i32) {
+        match face {
+            Face::Front => (self.width, self.height),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Like `Torus`, but leaving through the left or right edge mirrors v.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KleinBottle {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Topology for KleinBottle {
+    fn step(&self, pos: Position, dir: Direction) -> Option<(Position, Direction)> {
+        let (mut u, mut v) = offset(pos, dir);
+        if u < 0 || u >= self.width {
+            // Only horizontal moves cross this seam, so the heading is unchanged
+            u = u.rem_euclid(self.width);
+            v = self.height - 1 - v;
+        }
+        v = v.rem_euclid(self.height);
+        Some((Position { face: pos.face, u, v }, dir))
+    }
+
+    fn extent(&self, face: Face) -> (i32, i32) {
+        match face {
+            Face::Front => (self.width, self.height),
+            _ => (0, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn front(u: i32, v: i32) -> Position {
+        Position { face: Face::Front, u, v }
+    }
+
+    /// Odd and even cubes, plus corridor and slab shaped boxes.
+    fn boards() -> Vec<Cube> {
+        let mut boards: Vec<Cube> = [1, 2, 3, 4, 5, 10, 16].into_iter().map(Cube::new).collect();
+        boards.extend([Cube::cuboid(12, 8, 4), Cube::cuboid(3, 5, 2), Cube::cuboid(1, 4, 7), Cube::cuboid(2, 9, 9)]);
+        boards
+    }
+
+    #[test]
+    fn test_cube_step_back_returns_to_start() {
+        for cube in boards() {
+            for pos in cube.cells() {
+                for dir in Direction::ALL {
+                    let (next, heading) = cube.step(pos, dir).unwrap();
+                    assert!(cube.contains(next), "{cube:?}: {pos:?} {dir:?} -> {next:?} is off the board");
+                    let (back, back_heading) = cube.step(next, heading.opposite()).unwrap();
+                    assert_eq!(
+                        (back, back_heading),
+                        (pos, dir.opposite()),
+                        "{cube:?}: {pos:?} {dir:?} -> {next:?} {heading:?} does not reverse"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cube_cells_have_four_distinct_neighbors() {
+        for cube in boards() {
+            for pos in cube.cells() {
+                let mut neighbors: Vec<Position> = Direction::ALL
+                    .iter()
+                    .map(|&dir| cube.step(pos, dir).unwrap().0)
+                    .collect();
+                assert!(!neighbors.contains(&pos), "{cube:?}: {pos:?} neighbors itself");
+                neighbors.sort_by_key(|p| (p.face as u8, p.u, p.v));
+                neighbors.dedup();
+                assert_eq!(neighbors.len(), 4, "{cube:?}: {pos:?} has duplicate neighbors");
+            }
+        }
+    }
+
+    #[test]
+    fn test_cube_every_cell_reachable() {
+        for cube in boards() {
+            let cells = cube.cells();
+            let mut seen = vec![cells[0]];
+            let mut frontier = vec![cells[0]];
+            while let Some(pos) = frontier.pop() {
+                for dir in Direction::ALL {
+                    let (next, _) = cube.step(pos, dir).unwrap();
+                    if !seen.contains(&next) {
+                        seen.push(next);
+                        frontier.push(next);
+                    }
+                }
+            }
+            assert_eq!(seen.len(), cells.len(), "{cube:?}: not every cell is reachable");
+        }
+    }
+
+    #[test]
+    fn test_cuboid_remaps_between_faces_of_different_size() {
+        let slab = Cube::cuboid(12, 8, 4);
+        // Top is 12 × 4; its right edge is the top edge of the 4 × 8 Right face
+        let pos = Position { face: Face::Top, u: 11, v: 1 };
+        assert_eq!(
+            slab.step(pos, Direction::Right),
+            Some((Position { face: Face::Right, u: 1, v: 7 }, Direction::Down))
+        );
+        // Right's left edge meets Front's right edge at the same height
+        let pos = Position { face: Face::Right, u: 0, v: 5 };
+        assert_eq!(
+            slab.step(pos, Direction::Left),
+            Some((Position { face: Face::Front, u: 11, v: 5 }, Direction::Left))
+        );
+        assert_eq!(slab.cells().len(), 2 * (12 * 8 + 4 * 8 + 12 * 4));
+    }
+
+    #[test]
+    fn test_torus_wraps_both_edges() {
+        let torus = Torus { width: 8, height: 5 };
+        assert_eq!(torus.step(front(7, 2), Direction::Right), Some((front(0, 2), Direction::Right)));
+        assert_eq!(torus.step(front(3, 0), Direction::Down), Some((front(3, 4), Direction::Down)));
+    }
+
+    #[test]
+    fn test_walled_stops_at_edges() {
+        let walled = Walled { width: 8, height: 5 };
+        assert_eq!(walled.step(front(7, 2), Direction::Right), None);
+        assert_eq!(walled.step(front(6, 2), Direction::Right), Some((front(7, 2), Direction::Right)));
+        assert_eq!(walled.cells().len(), 40);
+    }
+
+    #[test]
+    fn test_klein_bottle_mirrors_across_seam() {
+        let klein = KleinBottle { width: 8, height: 5 };
+        assert_eq!(klein.step(front(7, 1), Direction::Right), Some((front(0, 3), Direction::Right)));
+        assert_eq!(klein.step(front(3, 4), Direction::Up), Some((front(3, 0), Direction::Up)));
+    }
+}
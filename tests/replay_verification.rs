@@ -0,0 +1,117 @@
+//! Leaderboard replay verification, run natively with no browser.
+
+use std::collections::{HashMap, VecDeque};
+
+use snake3d_rs::difficulty::{Difficulty, SpeedCurve, SpeedDriver};
+use snake3d_rs::game::{Direction, GameState, Position};
+use snake3d_rs::replay::{verify_score, Replay, VerifyError};
+use snake3d_rs::topology::Topology;
+
+/// First step of a shortest path from the head to the food, avoiding the body.
+fn path_to_food(game: &GameState) -> Option<Direction> {
+    let head = game.snake.head();
+    let mut first_step: HashMap<Position, Direction> = HashMap::new();
+    let mut frontier = VecDeque::new();
+    for dir in Direction::ALL {
+        let (next, _) = game.topology.step(head, dir)?;
+        if !game.snake.occupies(next) && !first_step.contains_key(&next) {
+            first_step.insert(next, dir);
+            frontier.push_back(next);
+        }
+    }
+    while let Some(pos) = frontier.pop_front() {
+        if game.food_at(pos).is_some() {
+            return first_step.get(&pos).copied();
+        }
+        let dir = first_step[&pos];
+        for step in Direction::ALL {
+            let (next, _) = game.topology.step(pos, step)?;
+            if !game.snake.occupies(next) && !first_step.contains_key(&next) {
+                first_step.insert(next, dir);
+                frontier.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Plays a short game with a food-seeking bot and returns its replay and score.
+fn play_bot_game(seed: u64) -> (Replay, u32) {
+    let mut game = GameState::with_seed(6, seed);
+    while !game.game_over && game.ticks < 400 {
+        if let Some(dir) = path_to_food(&game) {
+            game.queue_turn(dir);
+        }
+        game.update();
+    }
+    assert!(game.score > 0, "bot should eat something");
+    (game.replay(), game.score)
+}
+
+#[test]
+fn honest_replay_verifies() {
+    for seed in [1, 2, 3] {
+        let (replay, score) = play_bot_game(seed);
+        let replay = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+        assert_eq!(verify_score(&replay, score), Ok(()));
+    }
+}
+
+#[test]
+fn inflated_score_is_rejected() {
+    let (replay, score) = play_bot_game(4);
+    assert_eq!(
+        verify_score(&replay, score + 10),
+        Err(VerifyError::ScoreMismatch { claimed: score + 10, actual: score })
+    );
+}
+
+#[test]
+fn tampered_replay_is_rejected() {
+    let (replay, score) = play_bot_game(5);
+
+    // Same inputs against a different food sequence no longer add up
+    let mut reseeded = replay.clone();
+    reseeded.seed ^= 0xdead_beef;
+    assert!(verify_score(&reseeded, score).is_err());
+
+    // Inputs reordered in time
+    assert!(replay.inputs.len() >= 2, "bot should steer more than once");
+    let mut shuffled = replay.clone();
+    shuffled.inputs.reverse();
+    assert_eq!(verify_score(&shuffled, score), Err(VerifyError::Malformed));
+
+    // A claimed length far beyond any real game is refused without playing it
+    let mut endless = replay.clone();
+    endless.ticks = u64::MAX;
+    assert_eq!(verify_score(&endless, score), Err(VerifyError::Malformed));
+
+    let mut future = replay;
+    future.version += 1;
+    assert_eq!(verify_score(&future, score), Err(VerifyError::UnsupportedVersion(future.version)));
+}
+
+#[test]
+fn replay_of_a_non_endless_game_is_rejected() {
+    let (replay, score) = play_bot_game(6);
+
+    let mut targeted = replay.clone();
+    targeted.config.target_score = Some(score);
+    assert_eq!(verify_score(&targeted, score), Err(VerifyError::Malformed));
+
+    let mut crowded = replay.clone();
+    crowded.config.food_count = 50;
+    assert_eq!(verify_score(&crowded, score), Err(VerifyError::Malformed));
+
+    let mut lingering = replay.clone();
+    lingering.config.prize_lifetime = Some(10_000);
+    assert_eq!(verify_score(&lingering, score), Err(VerifyError::Malformed));
+
+    let mut flattened = replay.clone();
+    flattened.config.depth = 1;
+    assert_eq!(verify_score(&flattened, score), Err(VerifyError::Malformed));
+
+    let mut slowed = replay;
+    slowed.config.difficulty = Difficulty::Custom(SpeedCurve { start: 1.0, min: 1.0, step: 0.0, driver: SpeedDriver::Score });
+    assert_eq!(verify_score(&slowed, score), Err(VerifyError::Malformed));
+}